@@ -2,7 +2,14 @@ use std::path::PathBuf;
 
 use clap::{value_parser, Parser, Subcommand};
 
+use crate::color::observer::Observer;
+use crate::color::space::{Adaptation, ColorSpace, GamutMapping, WhitePoint};
+use crate::color::tone_mapping::Operator;
+use crate::image::FloatChannels;
+use crate::tracer::bvh::Builder;
+
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum Command {
     /// Trace and render the scene.
     Render {
@@ -29,13 +36,31 @@ pub enum Command {
         #[arg(short = 'g', long = "gamma", default_value = "1.0")]
         gamma: f64,
 
+        #[clap(flatten)]
+        tone_mapping_options: ToneMappingOptions,
+
+        #[clap(flatten)]
+        color_options: ColorOptions,
+
+        /// Channels of a floating-point output image (`.exr` or `.hdr`).
+        ///
+        /// The floating-point images contain the unscaled XYZ integrals of the spectral radiance.
+        #[arg(long = "float-channels", value_enum, default_value = "linear-rgb")]
+        float_channels: FloatChannels,
+
+        /// Standard colorimetric observer, overrides the scene one (CIE 1964 by default).
+        #[arg(long, value_enum)]
+        observer: Option<Observer>,
+
         /// Number of rendering threads (`0` for automatic choice).
         #[arg(short = 't', long = "threads", default_value = "0")]
         n_threads: usize,
 
-        /// Maximal number of surfaces in a single leaf of the bounding volume hierarchy.
-        #[arg(long, default_value = "8")]
-        max_bvh_leaf_size: usize,
+        #[clap(flatten)]
+        checkpoint_options: CheckpointOptions,
+
+        #[clap(flatten)]
+        bvh_options: BvhOptions,
 
         #[clap(flatten)]
         tracer_options: TracerOptions,
@@ -52,17 +77,106 @@ pub struct Args {
     pub command: Command,
 }
 
+#[derive(Parser)]
+pub struct CheckpointOptions {
+    /// Checkpoint file with the accumulated samples, which gets written during the rendering.
+    #[arg(long = "checkpoint", value_name = "PATH")]
+    pub path: Option<PathBuf>,
+
+    /// Minimal interval between the checkpoint writes, in seconds.
+    ///
+    /// The checkpoint is written after a rendering pass, and once more at the end.
+    #[arg(long = "checkpoint-interval", default_value = "60")]
+    pub interval_secs: u64,
+
+    /// Continue rendering from the checkpoint.
+    ///
+    /// With the same scene and options, the result is the same as of an uninterrupted run.
+    /// It is also possible to increase the number of samples for the finished rendering.
+    #[arg(long, requires = "path")]
+    pub resume: bool,
+}
+
+#[derive(Parser)]
+pub struct BvhOptions {
+    /// Maximal number of surfaces in a single leaf of the bounding volume hierarchy.
+    ///
+    /// The surface area heuristic may still create smaller leaves, when they're cheaper.
+    #[arg(long = "max-bvh-leaf-size", default_value = "8")]
+    pub max_leaf_size: usize,
+
+    /// Bounding volume hierarchy construction algorithm.
+    #[arg(long = "bvh-builder", value_enum, default_value = "median")]
+    pub builder: Builder,
+}
+
+/// Conversion of the XYZ colors into the output RGB color space.
+#[derive(Parser)]
+pub struct ColorOptions {
+    /// Output color space of the low dynamic range images, and of the linear RGB float images.
+    #[arg(long = "color-space", value_enum, default_value = "srgb")]
+    pub space: ColorSpace,
+
+    /// Scene white, which gets mapped onto the color space white point:
+    /// `e`, a black body temperature like `3200k`, or the `x,y` chromaticity.
+    ///
+    /// By default, the colors are not adapted.
+    #[arg(long)]
+    pub white_balance: Option<WhitePoint>,
+
+    /// Chromatic adaptation transform for the white balance.
+    #[arg(long, value_enum, default_value = "bradford")]
+    pub adaptation: Adaptation,
+
+    /// Handling of the out-of-gamut colors in the low dynamic range images.
+    #[arg(long, value_enum, default_value = "clip")]
+    pub gamut_mapping: GamutMapping,
+}
+
+/// Tone mapping of the low dynamic range output images.
+#[derive(Parser)]
+pub struct ToneMappingOptions {
+    /// Tone mapping operator.
+    #[arg(long = "tone-mapping", value_enum, default_value = "max-luminance")]
+    pub operator: Operator,
+
+    /// Luminance multiplier for the fixed exposure.
+    #[arg(long, default_value = "1")]
+    pub exposure: f64,
+
+    /// Luminance, to which the log-average luminance gets mapped before applying a tone curve.
+    #[arg(long, default_value = "0.18")]
+    pub key: f64,
+
+    /// Luminance percentile, which gets mapped to the full intensity by the auto-exposure.
+    #[arg(long, default_value = "99", value_parser = parse_percentile)]
+    pub percentile: f64,
+
+    /// White luminance for the extended Reinhard operator (defaults to the brightest pixel).
+    #[arg(long)]
+    pub white_luminance: Option<f64>,
+}
+
 #[derive(Parser)]
 pub struct TracerOptions {
     /// Number of different rays per pixel that get averaged to obtain a final color.
     #[arg(short = 's', long = "samples", default_value = "1", value_parser = value_parser!(u32).range(1..))]
     pub n_samples_per_pixel: u32,
 
+    /// Number of samples per pixel in a single rendering pass.
+    ///
+    /// The rendering is progressive: the passes get accumulated until the requested number
+    /// of samples is reached.
+    #[arg(long = "pass-samples", default_value = "16", value_parser = value_parser!(u32).range(1..))]
+    pub n_samples_per_pass: u32,
+
     /// Maximum number of ray bounces of the scene's surfaces.
     ///
     /// Each ray's bounce count gets decreased by one when the ray gets scattered.
     /// Once it reaches zero, no scattered rays get traced any more.
-    #[arg(short = 'b', long = "max-bounces", default_value = "5", value_parser = value_parser!(u32).range(1..))]
+    ///
+    /// This is a hard limit, the paths normally get terminated earlier by the Russian roulette.
+    #[arg(short = 'b', long = "max-bounces", default_value = "64", value_parser = value_parser!(u32).range(1..))]
     pub n_max_bounces: u32,
 
     /// Minimal distance from a ray's origin point to a possible hit.
@@ -76,7 +190,39 @@ pub struct TracerOptions {
     /// When the total attenuation drops below the setting, no scattered rays get traced any more.
     /// This saves some time because low attenuation doesn't contribute enough to the final intensity.
     ///
-    /// This helps a lot in, for example, a foggy environment.
-    #[arg(long, default_value = "0.000001")]
+    /// Note that it biases the image darker, prefer the Russian roulette instead.
+    /// It may still be useful for quick previews, for example, in a foggy environment.
+    #[arg(long, default_value = "0")]
     pub min_attenuation: f64,
+
+    /// Number of bounces, after which the paths get randomly terminated by the [Russian roulette][1].
+    ///
+    /// [1]: https://www.pbr-book.org/3ed-2018/Monte_Carlo_Integration/Russian_Roulette_and_Splitting
+    #[arg(long = "roulette-depth", default_value = "3")]
+    pub roulette_depth: u32,
+
+    /// Probability of a path to survive the Russian roulette at each bounce.
+    ///
+    /// The surviving paths get their contribution boosted accordingly, so that the image stays unbiased.
+    /// Lower probability makes rendering faster but noisier.
+    #[arg(long = "roulette-survival", default_value = "0.8", value_parser = parse_probability)]
+    pub roulette_survival_probability: f64,
+}
+
+fn parse_percentile(value: &str) -> Result<f64, String> {
+    let percentile: f64 = value.parse().map_err(|error| format!("{error}"))?;
+    if (0.0..=100.0).contains(&percentile) {
+        Ok(percentile)
+    } else {
+        Err(String::from("the percentile must be within [0, 100]"))
+    }
+}
+
+fn parse_probability(value: &str) -> Result<f64, String> {
+    let probability: f64 = value.parse().map_err(|error| format!("{error}"))?;
+    if probability > 0.0 && probability <= 1.0 {
+        Ok(probability)
+    } else {
+        Err(String::from("the probability must be within (0, 1]"))
+    }
 }
@@ -1,3 +1,7 @@
+pub mod cie_1931;
 pub mod cie_1964;
+pub mod observer;
 pub mod rgb;
+pub mod space;
+pub mod tone_mapping;
 pub mod xyz;
@@ -1,9 +1,5 @@
 use crate::math::vec3::Vec3;
 
-pub const XYZ_TO_RED: Vec3 = Vec3::new(3.2404542, -1.5371385, -0.4985314);
-pub const XYZ_TO_GREEN: Vec3 = Vec3::new(-0.9692660, 1.8760108, 0.0415560);
-pub const XYZ_TO_BLUE: Vec3 = Vec3::new(0.0556434, -0.2040259, 1.0572252);
-
 /// Wavelength to XYZ transformation table, 10° standard colorimetric observer.
 /// Starts at 360mm with 1mm step (and so ends with 830mm inclusive).
 // noinspection RsApproxConstant
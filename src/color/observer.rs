@@ -0,0 +1,32 @@
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::color::{cie_1931, cie_1964};
+use crate::math::vec3::Vec3;
+
+/// [CIE standard colorimetric observer][1], which defines the color matching functions.
+///
+/// [1]: https://en.wikipedia.org/wiki/CIE_1931_color_space#CIE_standard_observer
+#[derive(Copy, Clone, Default, Deserialize, JsonSchema, ValueEnum)]
+pub enum Observer {
+    /// CIE 1931 2° standard observer, which most of the color management and reference data use.
+    #[value(name = "cie1931")]
+    Cie1931,
+
+    /// CIE 1964 10° supplementary standard observer.
+    #[default]
+    #[value(name = "cie1964")]
+    Cie1964,
+}
+
+impl Observer {
+    /// Color matching functions, starting at 360nm with 1nm step.
+    #[inline]
+    pub const fn color_matching_functions(self) -> &'static [Vec3; 471] {
+        match self {
+            Self::Cie1931 => &cie_1931::WAVELENGTH_TO_XYZ,
+            Self::Cie1964 => &cie_1964::WAVELENGTH_TO_XYZ,
+        }
+    }
+}
@@ -1,9 +1,6 @@
 use std::ops::Mul;
 
-use crate::color::cie_1964::*;
-use crate::color::xyz::XyzColor;
 use crate::math::vec3::Vec3;
-use crate::physics::units::Length;
 
 /// RGB color represented as a 3-vector.
 #[derive(Debug)]
@@ -16,18 +13,10 @@ impl From<Vec3> for RgbColor {
     }
 }
 
-impl From<XyzColor> for RgbColor {
-    /// - https://en.wikipedia.org/wiki/SRGB#From_CIE_XYZ_to_sRGB
-    /// - https://stackoverflow.com/a/39446403/359730
+impl From<RgbColor> for Vec3 {
     #[inline]
-    fn from(value: XyzColor) -> Self {
-        let value = Vec3::from(value);
-        let srgb = Vec3::new(
-            Self::srgb_gamma_correction(value.dot(XYZ_TO_RED)),
-            Self::srgb_gamma_correction(value.dot(XYZ_TO_GREEN)),
-            Self::srgb_gamma_correction(value.dot(XYZ_TO_BLUE)),
-        );
-        Self(srgb.clamp(Vec3::ZERO, Vec3::ONE))
+    fn from(value: RgbColor) -> Self {
+        value.0
     }
 }
 
@@ -46,20 +35,6 @@ impl RgbColor {
     pub fn apply_gamma(self, gamma: f64) -> Self {
         Self(self.0.powf(gamma))
     }
-
-    #[inline]
-    pub fn from_wavelength(wavelength: Length) -> Self {
-        Self::from(XyzColor::from_wavelength(wavelength))
-    }
-
-    #[inline]
-    fn srgb_gamma_correction(linear_color: f64) -> f64 {
-        if linear_color <= 0.0031308 {
-            12.92 * linear_color
-        } else {
-            1.055 * linear_color.powf(1.0 / 2.4) - 0.055
-        }
-    }
 }
 
 impl From<RgbColor> for image::Rgb<u16> {
@@ -72,6 +47,13 @@ impl From<RgbColor> for image::Rgb<u16> {
     }
 }
 
+impl From<RgbColor> for image::Rgb<f32> {
+    #[inline]
+    fn from(value: RgbColor) -> Self {
+        Self::from([value.0.x as f32, value.0.y as f32, value.0.z as f32])
+    }
+}
+
 impl Mul<f64> for RgbColor {
     type Output = Self;
 
@@ -80,26 +62,3 @@ impl Mul<f64> for RgbColor {
         Self(self.0 * rhs)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn red_limit_ok() {
-        let color = RgbColor::from_wavelength(Length::from_nanos(700.0));
-        assert!(color.abs_diff_eq(&RgbColor::new(0.18, 0.0, 0.0), 0.01), "actual: {color:?}");
-    }
-
-    #[test]
-    fn blue_ok() {
-        let color = RgbColor::from_wavelength(Length::from_nanos(450.0));
-        assert!(color.abs_diff_eq(&RgbColor::new(0.29, 0.0, 1.0), 0.01), "actual: {color:?}");
-    }
-
-    #[test]
-    fn violet_limit_ok() {
-        let color = RgbColor::from_wavelength(Length::from_nanos(400.0));
-        assert!(color.abs_diff_eq(&RgbColor::new(0.13, 0.0, 0.33), 0.01), "actual: {color:?}");
-    }
-}
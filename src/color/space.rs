@@ -0,0 +1,400 @@
+use std::str::FromStr;
+
+use clap::ValueEnum;
+
+use crate::args::ColorOptions;
+use crate::color::observer::Observer;
+use crate::color::rgb::RgbColor;
+use crate::color::xyz::XyzColor;
+use crate::math::mat3::Mat3;
+use crate::math::vec3::Vec3;
+use crate::physics::optics::material::emittance::Emittance;
+use crate::physics::optics::material::property::Property;
+use crate::physics::units::*;
+
+/// Output RGB color space.
+#[derive(Copy, Clone, ValueEnum)]
+pub enum ColorSpace {
+    /// [sRGB](https://en.wikipedia.org/wiki/SRGB).
+    Srgb,
+
+    /// [Display P3](https://en.wikipedia.org/wiki/DCI-P3#Display_P3): the DCI-P3 primaries
+    /// with the D65 white point and the sRGB transfer function.
+    DisplayP3,
+
+    /// [ITU-R BT.2020](https://en.wikipedia.org/wiki/Rec._2020).
+    Rec2020,
+
+    /// [Adobe RGB (1998)](https://en.wikipedia.org/wiki/Adobe_RGB_color_space).
+    AdobeRgb,
+
+    /// [ACEScg](https://en.wikipedia.org/wiki/Academy_Color_Encoding_System#ACEScg):
+    /// the linear AP1 primaries with the ACES white point.
+    #[value(name = "acescg")]
+    AcesCg,
+}
+
+/// [Chromatic adaptation transform][1], which is used for the white balance.
+///
+/// [1]: https://en.wikipedia.org/wiki/Chromatic_adaptation
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Adaptation {
+    /// [Bradford transform](http://www.brucelindbloom.com/index.html?Eqn_ChromAdapt.html).
+    Bradford,
+
+    /// [CAT16](https://doi.org/10.1002/col.22131) from the CAM16 color appearance model.
+    #[value(name = "cat16")]
+    Cat16,
+}
+
+/// Handling of the colors, which are out of the output color space gamut.
+#[derive(Copy, Clone, ValueEnum)]
+pub enum GamutMapping {
+    /// Clamp each channel to `[0, 1]`, which may shift the hue.
+    Clip,
+
+    /// Desaturate the color towards the gray of the same luminance, until it fits into the gamut.
+    ///
+    /// The hue is preserved, unless the luminance itself exceeds one.
+    Desaturate,
+}
+
+/// Scene white point, which gets mapped onto the color space white point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WhitePoint {
+    /// [Equal-energy](https://en.wikipedia.org/wiki/Standard_illuminant#Illuminant_E) white.
+    E,
+
+    /// Black body of the given temperature, in kelvins.
+    BlackBody(f64),
+
+    /// Chromaticity coordinates.
+    Chromaticity(f64, f64),
+}
+
+/// Converts the XYZ colors into the output color space.
+pub struct ColorConverter {
+    space: ColorSpace,
+    gamut_mapping: GamutMapping,
+
+    /// XYZ to linear RGB transformation, including the chromatic adaptation.
+    matrix: Mat3,
+
+    /// Luminance of the linear RGB components.
+    luminance: Vec3,
+}
+
+impl ColorSpace {
+    /// Chromaticity coordinates of the red, green, and blue primaries.
+    const fn primaries(self) -> [(f64, f64); 3] {
+        match self {
+            Self::Srgb => [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)],
+            Self::DisplayP3 => [(0.680, 0.320), (0.265, 0.690), (0.150, 0.060)],
+            Self::Rec2020 => [(0.708, 0.292), (0.170, 0.797), (0.131, 0.046)],
+            Self::AdobeRgb => [(0.64, 0.33), (0.21, 0.71), (0.15, 0.06)],
+            Self::AcesCg => [(0.713, 0.293), (0.165, 0.830), (0.128, 0.044)],
+        }
+    }
+
+    /// Chromaticity coordinates of the white point.
+    const fn white_point(self) -> (f64, f64) {
+        match self {
+            Self::Srgb | Self::DisplayP3 | Self::Rec2020 | Self::AdobeRgb => (0.3127, 0.3290),
+            Self::AcesCg => (0.32168, 0.33767),
+        }
+    }
+
+    /// [Linear RGB to XYZ matrix][1], derived from the primaries and the white point.
+    ///
+    /// [1]: http://www.brucelindbloom.com/index.html?Eqn_RGB_XYZ_Matrix.html
+    pub fn rgb_to_xyz(self) -> Mat3 {
+        let primaries = self
+            .primaries()
+            .map(|(x, y)| Vec3::from(XyzColor::from_chromaticity(x, y)));
+        let (x, y) = self.white_point();
+        let scale =
+            Mat3::from_columns(primaries).inverse() * Vec3::from(XyzColor::from_chromaticity(x, y));
+        Mat3::from_columns([
+            primaries[0] * scale.x,
+            primaries[1] * scale.y,
+            primaries[2] * scale.z,
+        ])
+    }
+
+    /// Encode the linear component with the color space transfer function.
+    pub fn encode(self, linear: f64) -> f64 {
+        match self {
+            Self::Srgb | Self::DisplayP3 => {
+                if linear <= 0.0031308 {
+                    12.92 * linear
+                } else {
+                    1.055 * linear.powf(1.0 / 2.4) - 0.055
+                }
+            }
+            Self::Rec2020 => {
+                const ALPHA: f64 = 1.09929682680944;
+                const BETA: f64 = 0.018053968510807;
+                if linear < BETA {
+                    4.5 * linear
+                } else {
+                    ALPHA * linear.powf(0.45) - (ALPHA - 1.0)
+                }
+            }
+            Self::AdobeRgb => linear.powf(256.0 / 563.0),
+            Self::AcesCg => linear,
+        }
+    }
+}
+
+impl Adaptation {
+    /// Transformation from XYZ into the cone response domain.
+    const fn matrix(self) -> Mat3 {
+        match self {
+            Self::Bradford => Mat3::from_rows([
+                Vec3::new(0.8951, 0.2664, -0.1614),
+                Vec3::new(-0.7502, 1.7135, 0.0367),
+                Vec3::new(0.0389, -0.0685, 1.0296),
+            ]),
+            Self::Cat16 => Mat3::from_rows([
+                Vec3::new(0.401288, 0.650173, -0.051461),
+                Vec3::new(-0.250268, 1.204414, 0.045854),
+                Vec3::new(-0.002079, 0.048952, 0.953127),
+            ]),
+        }
+    }
+
+    /// Build the matrix, which maps the source white onto the destination one.
+    pub fn transform(self, source_white: XyzColor, destination_white: XyzColor) -> Mat3 {
+        let matrix = self.matrix();
+        let source = matrix * Vec3::from(source_white);
+        let destination = matrix * Vec3::from(destination_white);
+        matrix.inverse() * Mat3::diagonal(destination / source) * matrix
+    }
+}
+
+impl WhitePoint {
+    /// Unit-luminance white color for the observer.
+    pub fn to_xyz(self, observer: Observer) -> XyzColor {
+        let color = match self {
+            Self::E => XyzColor::from_spectrum(|_| 1.0, observer),
+            Self::BlackBody(temperature) => {
+                let emittance = Emittance::BlackBody {
+                    temperature: Temperature::from(temperature),
+                };
+                XyzColor::from_spectrum(|wavelength| emittance.at(wavelength).0, observer)
+            }
+            Self::Chromaticity(x, y) => XyzColor::from_chromaticity(x, y),
+        };
+        color / color.luminance()
+    }
+}
+
+impl FromStr for WhitePoint {
+    type Err = String;
+
+    /// Parse `e`, the black body temperature like `3200k`, or the `x,y` chromaticity.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("e") {
+            return Ok(Self::E);
+        }
+        if let Some(temperature) = value.strip_suffix(['k', 'K']) {
+            return match temperature.parse::<f64>() {
+                Ok(temperature) if temperature > 0.0 => Ok(Self::BlackBody(temperature)),
+                _ => Err(format!("`{temperature}` is not a valid temperature")),
+            };
+        }
+        let Some((x, y)) = value.split_once(',') else {
+            return Err("expected `e`, the temperature like `3200k`, or `x,y`".to_string());
+        };
+        match (x.trim().parse::<f64>(), y.trim().parse::<f64>()) {
+            (Ok(x), Ok(y)) if x > 0.0 && y > 0.0 && x + y < 1.0 => Ok(Self::Chromaticity(x, y)),
+            _ => Err(format!("`{value}` is not a valid chromaticity")),
+        }
+    }
+}
+
+impl ColorConverter {
+    pub fn new(options: &ColorOptions, observer: Observer) -> Self {
+        let rgb_to_xyz = options.space.rgb_to_xyz();
+        let mut xyz_to_rgb = rgb_to_xyz.inverse();
+        if let Some(white_balance) = options.white_balance {
+            let (x, y) = options.space.white_point();
+            let adaptation = options
+                .adaptation
+                .transform(white_balance.to_xyz(observer), XyzColor::from_chromaticity(x, y));
+            xyz_to_rgb = xyz_to_rgb * adaptation;
+        }
+        Self {
+            space: options.space,
+            gamut_mapping: options.gamut_mapping,
+            matrix: xyz_to_rgb,
+            luminance: rgb_to_xyz.row(1),
+        }
+    }
+
+    /// Convert to the linear RGB, without the gamut mapping.
+    #[inline]
+    pub fn linear_from(&self, color: XyzColor) -> RgbColor {
+        RgbColor::from(self.matrix * Vec3::from(color))
+    }
+
+    /// Convert to the gamut-mapped and encoded RGB.
+    pub fn encoded_from(&self, color: XyzColor) -> RgbColor {
+        let linear = Vec3::from(self.linear_from(color));
+        let mapped = match self.gamut_mapping {
+            GamutMapping::Clip => linear,
+            GamutMapping::Desaturate => self.desaturate(linear),
+        };
+        let mapped = mapped.clamp(Vec3::ZERO, Vec3::ONE);
+        RgbColor::new(
+            self.space.encode(mapped.x),
+            self.space.encode(mapped.y),
+            self.space.encode(mapped.z),
+        )
+    }
+
+    /// Move the linear color towards the gray of the same luminance, until it fits into the gamut.
+    fn desaturate(&self, color: Vec3) -> Vec3 {
+        let gray = self.luminance.dot(color);
+        if gray <= 0.0 {
+            return Vec3::ZERO;
+        }
+        if gray >= 1.0 {
+            return Vec3::ONE;
+        }
+        let factor = [color.x, color.y, color.z]
+            .into_iter()
+            .fold(1.0_f64, |factor, component| {
+                if component < 0.0 {
+                    factor.min(gray / (gray - component))
+                } else if component > 1.0 {
+                    factor.min((1.0 - gray) / (component - gray))
+                } else {
+                    factor
+                }
+            });
+        Vec3::splat(gray) + (color - Vec3::splat(gray)) * factor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::units::Length;
+
+    fn encode_wavelength(nanos: f64) -> RgbColor {
+        let options = ColorOptions {
+            space: ColorSpace::Srgb,
+            white_balance: None,
+            adaptation: Adaptation::Bradford,
+            gamut_mapping: GamutMapping::Clip,
+        };
+        let color = XyzColor::from_wavelength(Length::from_nanos(nanos), Observer::Cie1964);
+        ColorConverter::new(&options, Observer::Cie1964).encoded_from(color)
+    }
+
+    /// The published matrix is derived from the more precise D65 chromaticity, hence the tolerance.
+    #[test]
+    fn srgb_matrix_ok() {
+        let matrix = ColorSpace::Srgb.rgb_to_xyz().inverse();
+        for (row, expected) in [
+            Vec3::new(3.2404542, -1.5371385, -0.4985314),
+            Vec3::new(-0.9692660, 1.8760108, 0.0415560),
+            Vec3::new(0.0556434, -0.2040259, 1.0572252),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            assert!(matrix.row(row).abs_diff_eq(expected, 1e-3), "{:?}", matrix.row(row));
+        }
+    }
+
+    #[test]
+    fn red_limit_ok() {
+        let color = encode_wavelength(700.0);
+        assert!(color.abs_diff_eq(&RgbColor::new(0.18, 0.0, 0.0), 0.01), "actual: {color:?}");
+    }
+
+    #[test]
+    fn blue_ok() {
+        let color = encode_wavelength(450.0);
+        assert!(color.abs_diff_eq(&RgbColor::new(0.29, 0.0, 1.0), 0.01), "actual: {color:?}");
+    }
+
+    #[test]
+    fn violet_limit_ok() {
+        let color = encode_wavelength(400.0);
+        assert!(color.abs_diff_eq(&RgbColor::new(0.13, 0.0, 0.33), 0.01), "actual: {color:?}");
+    }
+
+    /// Luminance coefficients from the ITU-R BT.2020 itself.
+    #[test]
+    fn rec_2020_luminance_ok() {
+        let luminance = ColorSpace::Rec2020.rgb_to_xyz().row(1);
+        assert!(luminance.abs_diff_eq(Vec3::new(0.2627, 0.6780, 0.0593), 1e-4), "{luminance:?}");
+    }
+
+    /// D65 to D50 Bradford matrix as published by Bruce Lindbloom.
+    #[test]
+    fn bradford_ok() {
+        let d65 = XyzColor::from(Vec3::new(0.95047, 1.0, 1.08883));
+        let d50 = XyzColor::from(Vec3::new(0.96422, 1.0, 0.82521));
+        let matrix = Adaptation::Bradford.transform(d65, d50);
+        assert!(
+            matrix
+                .row(0)
+                .abs_diff_eq(Vec3::new(1.0478112, 0.0228866, -0.0501270), 1e-6)
+        );
+        assert!(
+            matrix
+                .row(1)
+                .abs_diff_eq(Vec3::new(0.0295424, 0.9904844, -0.0170491), 1e-6)
+        );
+        assert!(
+            matrix
+                .row(2)
+                .abs_diff_eq(Vec3::new(-0.0092345, 0.0150436, 0.7521316), 1e-6)
+        );
+    }
+
+    #[test]
+    fn white_balance_ok() {
+        let options = ColorOptions {
+            space: ColorSpace::DisplayP3,
+            white_balance: Some("3200k".parse().unwrap()),
+            adaptation: Adaptation::Cat16,
+            gamut_mapping: GamutMapping::Clip,
+        };
+        let converter = ColorConverter::new(&options, Observer::Cie1931);
+        let white = WhitePoint::BlackBody(3200.0).to_xyz(Observer::Cie1931);
+        let rgb = Vec3::from(converter.linear_from(white));
+        assert!(rgb.abs_diff_eq(Vec3::ONE, 1e-9), "{rgb:?}");
+    }
+
+    #[test]
+    fn desaturate_ok() {
+        let options = ColorOptions {
+            space: ColorSpace::Srgb,
+            white_balance: None,
+            adaptation: Adaptation::Bradford,
+            gamut_mapping: GamutMapping::Desaturate,
+        };
+        let converter = ColorConverter::new(&options, Observer::Cie1964);
+        let color = Vec3::new(-0.2, 0.5, 1.3);
+        let mapped = converter.desaturate(color);
+        assert!(
+            mapped.min_element() >= -1e-12 && mapped.max_element() <= 1.0 + 1e-12,
+            "{mapped:?}"
+        );
+        let luminance = converter.luminance;
+        assert!((luminance.dot(mapped) - luminance.dot(color)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn parse_white_point_ok() {
+        assert_eq!("E".parse(), Ok(WhitePoint::E));
+        assert_eq!("6500K".parse(), Ok(WhitePoint::BlackBody(6500.0)));
+        assert_eq!("0.3127, 0.329".parse(), Ok(WhitePoint::Chromaticity(0.3127, 0.329)));
+        assert!("0.3127".parse::<WhitePoint>().is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use clap::ValueEnum;
+
+use crate::args::ToneMappingOptions;
+use crate::color::space::ColorSpace;
+use crate::color::xyz::XyzColor;
+use crate::math::mat3::Mat3;
+use crate::math::vec3::Vec3;
+
+/// [Tone mapping][1] operator.
+///
+/// Except for ACES, the operators map the luminance, and scale the XYZ color accordingly,
+/// so that the chromaticity is preserved.
+///
+/// [1]: https://en.wikipedia.org/wiki/Tone_mapping
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Operator {
+    /// Divide by the brightest pixel luminance, unless it is below one.
+    MaxLuminance,
+
+    /// Multiply by the fixed exposure, and clip.
+    Exposure,
+
+    /// Scale so that the luminance percentile maps to one, and clip.
+    AutoExposure,
+
+    /// [Reinhard global operator][1], «L / (1 + L)», after scaling the log-average luminance to the key.
+    ///
+    /// [1]: https://www-old.cs.utah.edu/docs/techreports/2002/pdf/UUCS-02-001.pdf
+    Reinhard,
+
+    /// Reinhard operator, which maps the white luminance to one instead of the infinity.
+    ReinhardExtended,
+
+    /// [Narkowicz's fit][1] of the ACES filmic curve, after scaling the log-average luminance to the key.
+    ///
+    /// The curve is applied to each linear sRGB channel, so the bright colors get desaturated.
+    ///
+    /// [1]: https://knarkowicz.wordpress.com/2016/01/06/aces-filmic-tone-mapping-curve/
+    Aces,
+}
+
+/// Tone mapper, which is set up for a specific image.
+pub struct ToneMapper {
+    operator: Operator,
+
+    /// Luminance scale, which gets applied before the operator.
+    scale: f64,
+
+    /// Scaled white luminance for the extended Reinhard operator.
+    white_luminance: f64,
+
+    /// Linear sRGB to XYZ matrix, and its inverse, for the ACES operator.
+    srgb_to_xyz: Mat3,
+    xyz_to_srgb: Mat3,
+}
+
+impl ToneMapper {
+    /// Set up the tone mapping by the image luminance statistics.
+    ///
+    /// The physical camera `exposure_scale`, if any, replaces the operator's own luminance scale.
+    pub fn new(
+        options: &ToneMappingOptions,
+        luminances: &[f64],
+        exposure_scale: Option<f64>,
+    ) -> Self {
+        let max_luminance = luminances.iter().copied().fold(0.0, f64::max);
+        let scale = exposure_scale.unwrap_or_else(|| match options.operator {
+            Operator::MaxLuminance => 1.0 / max_luminance.max(1.0),
+            Operator::Exposure => options.exposure,
+            Operator::AutoExposure => 1.0 / Self::percentile(luminances, options.percentile),
+            Operator::Reinhard | Operator::ReinhardExtended | Operator::Aces => {
+                options.key / Self::log_average(luminances)
+            }
+        });
+        let scale = if scale.is_finite() { scale } else { 1.0 };
+        let srgb_to_xyz = ColorSpace::Srgb.rgb_to_xyz();
+        Self {
+            operator: options.operator,
+            scale,
+            white_luminance: options.white_luminance.unwrap_or(max_luminance) * scale,
+            srgb_to_xyz,
+            xyz_to_srgb: srgb_to_xyz.inverse(),
+        }
+    }
+
+    pub fn apply(&self, color: XyzColor) -> XyzColor {
+        if let Operator::Aces = self.operator {
+            let Vec3 { x, y, z } = self.xyz_to_srgb * Vec3::from(color * self.scale);
+            let mapped = Vec3::new(Self::aces(x), Self::aces(y), Self::aces(z));
+            return XyzColor::from(self.srgb_to_xyz * mapped);
+        }
+
+        let luminance = color.luminance() * self.scale;
+        if luminance <= 0.0 {
+            return color * self.scale;
+        }
+        let mapped_luminance = match self.operator {
+            Operator::MaxLuminance | Operator::Exposure | Operator::AutoExposure => luminance,
+            Operator::Reinhard => luminance / (1.0 + luminance),
+            Operator::ReinhardExtended => {
+                luminance * (1.0 + luminance / self.white_luminance.powi(2)) / (1.0 + luminance)
+            }
+            Operator::Aces => unreachable!(),
+        };
+        color * (self.scale * mapped_luminance / luminance)
+    }
+
+    #[inline]
+    fn aces(x: f64) -> f64 {
+        (x * (2.51 * x + 0.03) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0.0, 1.0)
+    }
+
+    /// Geometric mean of the non-zero luminances.
+    fn log_average(luminances: &[f64]) -> f64 {
+        let (sum, count) = luminances
+            .iter()
+            .filter(|luminance| **luminance > 0.0)
+            .fold((0.0, 0), |(sum, count), luminance| (sum + luminance.ln(), count + 1));
+        (sum / count as f64).exp()
+    }
+
+    fn percentile(luminances: &[f64], percentile: f64) -> f64 {
+        let mut luminances = luminances.to_vec();
+        luminances.sort_unstable_by(f64::total_cmp);
+        let index = (percentile / 100.0 * (luminances.len() as f64 - 1.0)).round() as usize;
+        luminances.get(index).copied().unwrap_or(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::color::observer::Observer;
+    use crate::physics::units::Length;
+
+    const fn new_options(operator: Operator) -> ToneMappingOptions {
+        ToneMappingOptions {
+            operator,
+            exposure: 1.0,
+            key: 0.18,
+            percentile: 50.0,
+            white_luminance: None,
+        }
+    }
+
+    /// Dim images are not brightened, as it has always been.
+    #[test]
+    fn max_luminance_ok() {
+        let options = new_options(Operator::MaxLuminance);
+        assert_abs_diff_eq!(ToneMapper::new(&options, &[0.25, 0.5], None).scale, 1.0);
+        assert_abs_diff_eq!(ToneMapper::new(&options, &[2.0, 4.0], None).scale, 0.25);
+    }
+
+    #[test]
+    fn auto_exposure_ok() {
+        let mapper = ToneMapper::new(
+            &new_options(Operator::AutoExposure),
+            &[4.0, 1.0, 1000.0, 2.0, 3.0],
+            None,
+        );
+        assert_abs_diff_eq!(mapper.scale, 1.0 / 3.0);
+    }
+
+    #[test]
+    fn reinhard_extended_white_ok() {
+        let luminances = [0.5, 2.0, 8.0];
+        let mapper = ToneMapper::new(&new_options(Operator::ReinhardExtended), &luminances, None);
+        let color = XyzColor::from_wavelength(Length::from_nanos(555.0), Observer::Cie1964);
+        let scale = 8.0 / color.luminance();
+        let color = color * scale;
+        assert_abs_diff_eq!(mapper.apply(color).luminance(), 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn exposure_scale_ok() {
+        let mapper =
+            ToneMapper::new(&new_options(Operator::AutoExposure), &[1.0, 2.0, 3.0], Some(0.25));
+        assert_abs_diff_eq!(mapper.scale, 0.25);
+    }
+}
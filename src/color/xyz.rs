@@ -1,7 +1,7 @@
 use std::iter::Sum;
-use std::ops::{Div, Mul};
+use std::ops::{AddAssign, Div, Mul};
 
-use crate::color::cie_1964::WAVELENGTH_TO_XYZ;
+use crate::color::observer::Observer;
 use crate::math::vec3::Vec3;
 use crate::physics::units::Length;
 
@@ -9,17 +9,35 @@ use crate::physics::units::Length;
 /// and `X` is a mix of the three CIE RGB curves chosen to be non-negative.
 ///
 /// [1]: https://en.wikipedia.org/wiki/CIE_1931_color_space#Meaning_of_X,_Y_and_Z
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 #[must_use]
 pub struct XyzColor(Vec3);
 
 impl XyzColor {
-    pub fn from_wavelength(wavelength: Length) -> Self {
-        let nanos = wavelength.0 / 1e-9;
-        let fract = nanos.fract();
-        let nanos = nanos as usize - 360;
-        assert!(nanos < 470, "actual: {nanos}, wavelength = {wavelength}");
-        Self((1.0 - fract) * WAVELENGTH_TO_XYZ[nanos] + fract * WAVELENGTH_TO_XYZ[nanos + 1])
+    pub fn from_wavelength(wavelength: Length, observer: Observer) -> Self {
+        let nanos = wavelength.0 / 1e-9 - 360.0;
+        assert!((0.0..=470.0).contains(&nanos), "wavelength = {wavelength}");
+        let nanos_floor = (nanos as usize).min(469);
+        let fract = nanos - nanos_floor as f64;
+        let table = observer.color_matching_functions();
+        Self((1.0 - fract) * table[nanos_floor] + fract * table[nanos_floor + 1])
+    }
+
+    /// Integrate the spectral distribution, which is given in watts per cubic meter,
+    /// with the 1nm step over the whole table.
+    pub fn from_spectrum(spectrum: impl Fn(Length) -> f64, observer: Observer) -> Self {
+        (360..=830)
+            .map(|nanos| {
+                let wavelength = Length::from_nanos(nanos as f64);
+                Self::from_wavelength(wavelength, observer) * (spectrum(wavelength) * 1e-9)
+            })
+            .sum()
+    }
+
+    /// Color with the unit luminance and the given chromaticity coordinates.
+    #[inline]
+    pub fn from_chromaticity(x: f64, y: f64) -> Self {
+        Self(Vec3::new(x / y, 1.0, (1.0 - x - y) / y))
     }
 
     #[inline]
@@ -33,6 +51,14 @@ impl XyzColor {
     pub fn max_element(&self) -> f64 {
         self.0.max_element()
     }
+
+    /// Chromaticity coordinates `x` and `y`.
+    #[cfg(test)]
+    #[must_use]
+    pub fn chromaticity(&self) -> (f64, f64) {
+        let sum = self.0.x + self.0.y + self.0.z;
+        (self.0.x / sum, self.0.y / sum)
+    }
 }
 
 impl Sum<XyzColor> for XyzColor {
@@ -45,6 +71,13 @@ impl Sum<XyzColor> for XyzColor {
     }
 }
 
+impl AddAssign for XyzColor {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
 impl Mul<f64> for XyzColor {
     type Output = Self;
 
@@ -63,9 +96,78 @@ impl Div<f64> for XyzColor {
     }
 }
 
+impl From<XyzColor> for image::Rgb<f32> {
+    /// Store the tristimulus values in the red, green, and blue channels respectively.
+    #[inline]
+    fn from(value: XyzColor) -> Self {
+        Self::from([value.0.x as f32, value.0.y as f32, value.0.z as f32])
+    }
+}
+
+impl From<Vec3> for XyzColor {
+    #[inline]
+    fn from(value: Vec3) -> Self {
+        Self(value)
+    }
+}
+
 impl From<XyzColor> for Vec3 {
     #[inline]
     fn from(value: XyzColor) -> Self {
         value.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::physics::optics::material::emittance::Emittance;
+    use crate::physics::optics::material::property::Property;
+    use crate::physics::units::*;
+
+    #[test]
+    fn monochromatic_ok() {
+        let wavelength = Length::from_nanos(520.0);
+        let color = XyzColor::from_wavelength(wavelength, Observer::Cie1931);
+        assert!(color.0.abs_diff_eq(Vec3::new(0.06327, 0.71, 0.07825), 1e-5), "{color:?}");
+        let color = XyzColor::from_wavelength(wavelength, Observer::Cie1964);
+        assert!(
+            color
+                .0
+                .abs_diff_eq(Vec3::new(0.117749, 0.761757, 0.060709), 1e-5),
+            "{color:?}"
+        );
+    }
+
+    /// The equal-energy illuminant must be at the center of the chromaticity diagram.
+    #[test]
+    fn equal_energy_white_point_ok() {
+        let emittance = Emittance::Constant { density: Quantity::from(1.0) };
+        for observer in [Observer::Cie1931, Observer::Cie1964] {
+            let (x, y) = XyzColor::from_spectrum(|wavelength| emittance.at(wavelength).0, observer)
+                .chromaticity();
+            assert_abs_diff_eq!(x, 1.0 / 3.0, epsilon = 1e-4);
+            assert_abs_diff_eq!(y, 1.0 / 3.0, epsilon = 1e-4);
+        }
+    }
+
+    /// CIE standard illuminant A is the 2856K black body.
+    #[test]
+    fn illuminant_a_white_point_ok() {
+        let emittance = Emittance::BlackBody {
+            temperature: Temperature::from(2856.0),
+        };
+        let (x, y) =
+            XyzColor::from_spectrum(|wavelength| emittance.at(wavelength).0, Observer::Cie1931)
+                .chromaticity();
+        assert_abs_diff_eq!(x, 0.44757, epsilon = 5e-4);
+        assert_abs_diff_eq!(y, 0.40745, epsilon = 5e-4);
+        let (x, y) =
+            XyzColor::from_spectrum(|wavelength| emittance.at(wavelength).0, Observer::Cie1964)
+                .chromaticity();
+        assert_abs_diff_eq!(x, 0.45117, epsilon = 5e-4);
+        assert_abs_diff_eq!(y, 0.40594, epsilon = 5e-4);
+    }
+}
@@ -1,3 +1,73 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use clap::ValueEnum;
+use image::codecs::hdr::HdrEncoder;
 use image::{ImageBuffer, Rgb};
 
+use crate::prelude::*;
+
 pub type Rgb16Image = ImageBuffer<Rgb<u16>, Vec<u16>>;
+pub type Rgb32FImage = ImageBuffer<Rgb<f32>, Vec<f32>>;
+
+/// Channels of a floating-point output image.
+#[derive(Copy, Clone, ValueEnum)]
+pub enum FloatChannels {
+    /// CIE XYZ tristimulus values, stored in the red, green, and blue channels respectively.
+    Xyz,
+
+    /// Linear RGB in the output color space, after the white balance.
+    ///
+    /// Out-of-gamut colors keep their negative components in OpenEXR,
+    /// but get clipped in Radiance HDR, which cannot store them.
+    #[value(alias = "linear-srgb")]
+    LinearRgb,
+}
+
+/// Floating-point output image format.
+#[derive(Copy, Clone)]
+pub enum FloatFormat {
+    /// [OpenEXR](https://en.wikipedia.org/wiki/OpenEXR).
+    OpenExr,
+
+    /// [Radiance HDR](https://en.wikipedia.org/wiki/RGBE_image_format).
+    RadianceHdr,
+}
+
+impl FloatFormat {
+    /// Get the floating-point format, which the path requires by its extension, if any.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?;
+        if extension.eq_ignore_ascii_case("exr") {
+            Some(Self::OpenExr)
+        } else if extension.eq_ignore_ascii_case("hdr") {
+            Some(Self::RadianceHdr)
+        } else {
+            None
+        }
+    }
+}
+
+/// Save the floating-point image in the format.
+pub fn save_float_image(image: &Rgb32FImage, path: &Path, format: FloatFormat) -> Result {
+    match format {
+        FloatFormat::RadianceHdr => {
+            // The `image` crate does not support the Radiance HDR in `save()`:
+            let pixels: Vec<Rgb<f32>> = image
+                .pixels()
+                .map(|pixel| Rgb(pixel.0.map(|channel| channel.max(0.0))))
+                .collect();
+            let writer = BufWriter::new(
+                File::create(path).with_context(|| format!("failed to create `{path:?}`"))?,
+            );
+            HdrEncoder::new(writer).encode(
+                &pixels,
+                image.width() as usize,
+                image.height() as usize,
+            )?;
+        }
+        FloatFormat::OpenExr => image.save(path)?,
+    }
+    Ok(())
+}
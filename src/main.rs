@@ -17,14 +17,18 @@
     clippy::unused_self
 )]
 
+use std::time::{Duration, Instant};
+use std::{fs, iter};
+
 use clap::Parser;
 use schemars::schema_for;
 use tracing_subscriber::FmtSubscriber;
 
-use crate::args::{Args, Command};
-use crate::color::rgb::RgbColor;
+use crate::args::{Args, Command, ToneMappingOptions};
+use crate::color::space::ColorConverter;
+use crate::color::tone_mapping::ToneMapper;
 use crate::color::xyz::XyzColor;
-use crate::image::Rgb16Image;
+use crate::image::{save_float_image, FloatChannels, FloatFormat, Rgb16Image, Rgb32FImage};
 
 mod args;
 mod color;
@@ -38,7 +42,7 @@ mod tracer;
 
 use crate::prelude::*;
 use crate::scene::Scene;
-use crate::tracer::bvh::Bvh;
+use crate::tracer::frame::Frame;
 use crate::tracer::progress::new_progress;
 use crate::tracer::Tracer;
 
@@ -52,9 +56,14 @@ fn main() -> Result {
             output_width,
             output_height,
             gamma,
+            tone_mapping_options,
+            color_options,
+            float_channels,
+            observer,
             output_path,
             n_threads,
-            max_bvh_leaf_size,
+            checkpoint_options,
+            bvh_options,
         } => {
             rayon::ThreadPoolBuilder::new()
                 .num_threads(n_threads)
@@ -62,21 +71,93 @@ fn main() -> Result {
             info!(current_num_threads = rayon::current_num_threads());
 
             let mut scene = Scene::read_from(&input_path)?;
+            let exposure_scale = scene.camera.exposure_scale()?;
+            info!(exposure_scale);
             info!(n_surfaces = scene.surfaces.len(), "building bounded volume hierarchy…");
-            let bvh = Bvh::new(&mut scene.surfaces, max_bvh_leaf_size);
+            let bvh = scene.build_bvh(&bvh_options)?;
+            let statistics = bvh.statistics();
+            info!(
+                depth = statistics.depth,
+                n_nodes = statistics.n_nodes,
+                n_leaves = statistics.n_leaves,
+                max_leaf_size = statistics.max_leaf_size,
+                sah_cost = statistics.sah_cost,
+                "built the hierarchy",
+            );
+
+            let observer = observer.or(scene.observer).unwrap_or_default();
+            let files = iter::once(&input_path)
+                .chain(&scene.referenced_paths)
+                .map(|path| fs::read(path).with_context(|| format!("failed to read `{path:?}`")))
+                .collect::<Result<Vec<_>>>()?;
+            let fingerprint =
+                Frame::fingerprint_of(&files, observer, &tracer_options, &bvh_options);
+            let mut frame = if checkpoint_options.resume
+                && let Some(path) = &checkpoint_options.path
+            {
+                let frame = Frame::read_from(path).context("failed to read the checkpoint")?;
+                if (frame.width(), frame.height()) != (output_width, output_height) {
+                    bail!(
+                        "the checkpoint is {}×{}, but the output is {output_width}×{output_height}",
+                        frame.width(),
+                        frame.height(),
+                    );
+                }
+                if frame.fingerprint() != fingerprint {
+                    bail!("the checkpoint was rendered from another scene, or with other options");
+                }
+                info!(n_samples = frame.min_n_samples(), "resuming");
+                frame
+            } else {
+                Frame::new(output_width, output_height, fingerprint)
+            };
 
-            let pixels = Tracer::new(
+            let tracer = Tracer::new(
                 bvh,
                 scene.ambient_emittance,
+                scene.sky,
+                observer,
                 scene.camera,
                 tracer_options,
                 output_width,
                 output_height,
-            )
-            .trace()?;
-            convert_pixels_to_image(output_width, pixels, gamma)?
-                .save(output_path)
-                .context("failed to save the output image")?;
+            );
+            let checkpoint_interval = Duration::from_secs(checkpoint_options.interval_secs);
+            let mut last_checkpoint_time = Instant::now();
+            tracer.trace(&mut frame, |frame| {
+                if let Some(path) = &checkpoint_options.path
+                    && last_checkpoint_time.elapsed() >= checkpoint_interval
+                {
+                    frame.write_to(path).context("failed to write the checkpoint")?;
+                    last_checkpoint_time = Instant::now();
+                }
+                Ok(())
+            })?;
+            if let Some(path) = &checkpoint_options.path {
+                frame
+                    .write_to(path)
+                    .context("failed to write the checkpoint")?;
+            }
+
+            let pixels = frame.to_rows();
+            let converter = ColorConverter::new(&color_options, observer);
+            if let Some(format) = FloatFormat::from_path(&output_path) {
+                let image =
+                    convert_pixels_to_float_image(output_width, pixels, float_channels, &converter);
+                save_float_image(&image, &output_path, format)
+            } else {
+                convert_pixels_to_image(
+                    output_width,
+                    pixels,
+                    gamma,
+                    &tone_mapping_options,
+                    exposure_scale,
+                    &converter,
+                )?
+                .save(&output_path)
+                .map_err(Into::into)
+            }
+            .context("failed to save the output image")?;
         }
 
         Command::Schema => {
@@ -90,26 +171,45 @@ fn convert_pixels_to_image(
     output_width: u32,
     rows: Vec<(u32, Vec<XyzColor>)>,
     gamma: f64,
+    tone_mapping_options: &ToneMappingOptions,
+    exposure_scale: Option<f64>,
+    converter: &ColorConverter,
 ) -> Result<Rgb16Image> {
-    let max_luminance = rows
+    let luminances: Vec<f64> = rows
         .iter()
         .flat_map(|(_, row)| row)
-        .map(|pixel| pixel.luminance())
-        .max_by(|lhs, rhs| lhs.total_cmp(rhs))
-        .unwrap_or(1.0)
-        .max(1.0);
-    let scale = 1.0 / max_luminance;
-    info!(max_luminance, scale);
+        .map(XyzColor::luminance)
+        .collect();
+    let tone_mapper = ToneMapper::new(tone_mapping_options, &luminances, exposure_scale);
 
     let mut image = Rgb16Image::new(output_width, rows.len() as u32);
     let progress = new_progress(rows.len() as u64, "converting to image")?;
     for (y, row) in rows {
         for (x, color) in row.into_iter().enumerate() {
-            let srgb_color = RgbColor::from(color * scale);
-            image.put_pixel(x as u32, y, srgb_color.apply_gamma(gamma).into());
+            let rgb_color = converter.encoded_from(tone_mapper.apply(color));
+            image.put_pixel(x as u32, y, rgb_color.apply_gamma(gamma).into());
         }
         progress.inc(1);
     }
     progress.finish();
     Ok(image)
 }
+
+fn convert_pixels_to_float_image(
+    output_width: u32,
+    rows: Vec<(u32, Vec<XyzColor>)>,
+    channels: FloatChannels,
+    converter: &ColorConverter,
+) -> Rgb32FImage {
+    let mut image = Rgb32FImage::new(output_width, rows.len() as u32);
+    for (y, row) in rows {
+        for (x, color) in row.into_iter().enumerate() {
+            let pixel = match channels {
+                FloatChannels::Xyz => color.into(),
+                FloatChannels::LinearRgb => converter.linear_from(color).into(),
+            };
+            image.put_pixel(x as u32, y, pixel);
+        }
+    }
+    image
+}
@@ -1,6 +1,9 @@
 pub mod aabb;
 pub mod hit;
+pub mod mat3;
+pub mod noise;
 pub mod ray;
 pub mod sequence;
+pub mod transform;
 pub mod vec2;
 pub mod vec3;
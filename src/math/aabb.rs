@@ -29,6 +29,13 @@ impl Aabb {
         self.min_point + self.size() / 2.0
     }
 
+    #[inline]
+    #[must_use]
+    pub fn surface_area(&self) -> f64 {
+        let size = self.size();
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
     /// See the original: <https://gamedev.stackexchange.com/a/18459/171067>.
     pub fn hit(&self, by_ray: &Ray, distance_range: &Range<f64>) -> Option<(f64, f64)> {
         if self.min_point.is_infinite() && self.max_point.is_infinite() {
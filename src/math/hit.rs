@@ -15,6 +15,11 @@ pub struct Hit<'a> {
     /// Normal at the hit point.
     pub normal: Vec3,
 
+    /// Geometric normal at the hit point, which faces the same side as the normal.
+    ///
+    /// It differs from the normal on the smooth-shaded triangles.
+    pub face_normal: Vec3,
+
     /// Distance travelled by the ray till the hit point.
     ///
     /// The ray direction **must** be normalized for this to hold.
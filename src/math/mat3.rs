@@ -0,0 +1,95 @@
+use std::ops::Mul;
+
+use crate::math::vec3::Vec3;
+
+/// 3×3 matrix represented by its rows.
+#[derive(Copy, Clone, Debug)]
+#[must_use]
+pub struct Mat3([Vec3; 3]);
+
+impl Mat3 {
+    pub const IDENTITY: Self = Self::diagonal(Vec3::ONE);
+
+    #[inline]
+    pub const fn from_rows(rows: [Vec3; 3]) -> Self {
+        Self(rows)
+    }
+
+    #[inline]
+    pub const fn from_columns(columns: [Vec3; 3]) -> Self {
+        Self::from_rows(columns).transpose()
+    }
+
+    #[inline]
+    pub const fn diagonal(diagonal: Vec3) -> Self {
+        Self([
+            Vec3::new(diagonal.x, 0.0, 0.0),
+            Vec3::new(0.0, diagonal.y, 0.0),
+            Vec3::new(0.0, 0.0, diagonal.z),
+        ])
+    }
+
+    #[inline]
+    pub const fn row(&self, index: usize) -> Vec3 {
+        self.0[index]
+    }
+
+    #[inline]
+    pub const fn transpose(self) -> Self {
+        let [a, b, c] = self.0;
+        Self([
+            Vec3::new(a.x, b.x, c.x),
+            Vec3::new(a.y, b.y, c.y),
+            Vec3::new(a.z, b.z, c.z),
+        ])
+    }
+
+    /// Invert the matrix via the [cross products][1] of its rows.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Invertible_matrix#Inversion_of_3_%C3%97_3_matrices
+    pub fn inverse(self) -> Self {
+        let [a, b, c] = self.0;
+        let determinant = a.dot(b.cross(c));
+        Self::from_columns([
+            b.cross(c) / determinant,
+            c.cross(a) / determinant,
+            a.cross(b) / determinant,
+        ])
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    #[inline]
+    fn mul(self, rhs: Vec3) -> Self::Output {
+        Vec3::new(self.0[0].dot(rhs), self.0[1].dot(rhs), self.0[2].dot(rhs))
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        let columns = rhs.transpose().0;
+        Self::from_columns(columns.map(|column| self * column))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverse_ok() {
+        let matrix = Mat3::from_rows([
+            Vec3::new(2.0, 1.0, 0.0),
+            Vec3::new(0.0, 3.0, 1.0),
+            Vec3::new(1.0, 0.0, 4.0),
+        ]);
+        let vector = Vec3::new(1.0, -2.0, 3.0);
+        assert!((matrix.inverse() * (matrix * vector)).abs_diff_eq(vector, 1e-12));
+        assert!(((matrix * matrix.inverse()) * vector).abs_diff_eq(vector, 1e-12));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::math::vec3::Vec3;
+
+/// Smooth 3D [value noise][1] in `[0, 1]`, with the unit lattice cell.
+///
+/// [1]: https://en.wikipedia.org/wiki/Value_noise
+pub fn value_noise(point: Vec3) -> f64 {
+    let (x, y, z) = (point.x.floor(), point.y.floor(), point.z.floor());
+    let (u, v, w) = (fade(point.x - x), fade(point.y - y), fade(point.z - z));
+    let (x, y, z) = (x as i64, y as i64, z as i64);
+
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+    let corner = |dx, dy, dz| lattice_value(x + dx, y + dy, z + dz);
+    lerp(
+        lerp(
+            lerp(corner(0, 0, 0), corner(1, 0, 0), u),
+            lerp(corner(0, 1, 0), corner(1, 1, 0), u),
+            v,
+        ),
+        lerp(
+            lerp(corner(0, 0, 1), corner(1, 0, 1), u),
+            lerp(corner(0, 1, 1), corner(1, 1, 1), u),
+            v,
+        ),
+        w,
+    )
+}
+
+/// [Fractional Brownian motion][1] of the value noise, in `[0, 1]`.
+///
+/// Each octave doubles the frequency, and halves the amplitude.
+///
+/// [1]: https://iquilezles.org/articles/fbm/
+pub fn fractal_noise(point: Vec3, n_octaves: u32) -> f64 {
+    let mut sum = 0.0;
+    let mut total_amplitude = 0.0;
+    let mut amplitude = 1.0;
+    let mut point = point;
+    for _ in 0..n_octaves.max(1) {
+        sum += amplitude * value_noise(point);
+        total_amplitude += amplitude;
+        amplitude *= 0.5;
+        // Shift the octaves apart, so that their lattices do not align:
+        point = point * 2.0 + Vec3::splat(17.31);
+    }
+    sum / total_amplitude
+}
+
+/// Quintic smoothstep, which has the continuous second derivative.
+#[inline]
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Pseudo-random value of the lattice point, in `[0, 1]`.
+#[inline]
+fn lattice_value(x: i64, y: i64, z: i64) -> f64 {
+    let mut hash = (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (z as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    hash ^= hash >> 33;
+    (hash >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_noise_ok() {
+        let noise = |i: i32| value_noise(Vec3::new(f64::from(i) * 0.01, 0.3, -0.7));
+        let mut previous = noise(0);
+        for i in 1..1000 {
+            let value = noise(i);
+            assert!((0.0..=1.0).contains(&value));
+            // It's continuous:
+            assert!((value - previous).abs() < 0.05);
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn fractal_noise_ok() {
+        for i in 0..1000 {
+            let value = fractal_noise(Vec3::new(f64::from(i) * 0.37, -1.3, 2.9), 5);
+            assert!((0.0..=1.0).contains(&value));
+        }
+    }
+}
@@ -15,6 +15,11 @@ impl RandomSequence {
     pub fn new() -> Self {
         Self(Rng::new())
     }
+
+    #[inline]
+    pub const fn with_seed(seed: u64) -> Self {
+        Self(Rng::with_seed(seed))
+    }
 }
 
 impl Sequence<f64> for RandomSequence {
@@ -56,6 +61,19 @@ impl VanDerCorput {
         self.offset = offset;
         self
     }
+
+    /// Skip the first `n` elements, as if [`Sequence::next`] has been called `n` times.
+    pub const fn skip(mut self, mut n: u64) -> Self {
+        // The state is the radical inverse of `n`, as a fraction with the base power denominator:
+        self.n = 0;
+        self.d = 1;
+        while n != 0 {
+            self.n = self.n * self.base + n % self.base;
+            self.d *= self.base;
+            n /= self.base;
+        }
+        self
+    }
 }
 
 impl Sequence<f64> for VanDerCorput {
@@ -97,6 +115,13 @@ impl Halton2 {
         self.corput_2 = self.corput_2.offset(offset.y);
         self
     }
+
+    /// Skip the first `n` elements.
+    pub const fn skip(mut self, n: u64) -> Self {
+        self.corput_1 = self.corput_1.skip(n);
+        self.corput_2 = self.corput_2.skip(n);
+        self
+    }
 }
 
 impl Sequence<Vec2> for Halton2 {
@@ -125,4 +150,12 @@ mod tests {
         assert_eq!(sequence.next(), 0.875);
         assert_eq!(sequence.next(), 0.0625);
     }
+
+    #[test]
+    fn corput_skip_ok() {
+        let mut sequence = VanDerCorput::new(3);
+        for n in 0..100 {
+            assert_eq!(VanDerCorput::new(3).skip(n).next(), sequence.next(), "n: {n}");
+        }
+    }
 }
@@ -0,0 +1,269 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::math::aabb::Aabb;
+use crate::math::mat3::Mat3;
+use crate::math::vec3::Vec3;
+use crate::prelude::*;
+
+/// Affine transformation, along with its inverse.
+///
+/// It deserializes from a list of [`Transformation`]s, which get applied in the given order.
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(try_from = "Vec<Transformation>")]
+pub struct Transform {
+    matrix: Affine,
+    inverse: Affine,
+}
+
+/// Linear part and the translation of an affine transformation.
+#[derive(Copy, Clone, Debug)]
+struct Affine {
+    linear: Mat3,
+    translation: Vec3,
+}
+
+/// Elementary transformation.
+#[derive(Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Transformation {
+    Translate {
+        by: Vec3,
+    },
+
+    /// Scale along the axes, may also be used to mirror.
+    Scale {
+        by: Vec3,
+    },
+
+    /// Rotate around the axis, which goes through the origin.
+    Rotate {
+        axis: Vec3,
+
+        /// Counterclockwise angle, in degrees.
+        angle: f64,
+    },
+}
+
+impl Default for Transform {
+    #[inline]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl TryFrom<Vec<Transformation>> for Transform {
+    type Error = anyhow::Error;
+
+    fn try_from(transformations: Vec<Transformation>) -> Result<Self> {
+        transformations
+            .into_iter()
+            .try_fold(Self::IDENTITY, |transform, transformation| {
+                Ok(transform.then(Self::try_from(transformation)?))
+            })
+    }
+}
+
+impl TryFrom<Transformation> for Transform {
+    type Error = anyhow::Error;
+
+    /// Build the transformation, rejecting those which cannot be inverted.
+    fn try_from(transformation: Transformation) -> Result<Self> {
+        match transformation {
+            Transformation::Translate { by } => {
+                if !by.is_finite() {
+                    bail!("the translation must be finite, got {by}");
+                }
+                Ok(Self::translation(by))
+            }
+            Transformation::Scale { by } => {
+                if !by.is_finite() || by.x == 0.0 || by.y == 0.0 || by.z == 0.0 {
+                    bail!("the scale must be finite and non-zero along each axis, got {by}");
+                }
+                Ok(Self::scaling(by))
+            }
+            Transformation::Rotate { axis, angle } => {
+                if !axis.is_finite() || axis.length_squared() == 0.0 {
+                    bail!("the rotation axis must be a finite non-zero vector, got {axis}");
+                }
+                if !angle.is_finite() {
+                    bail!("the rotation angle must be finite, got {angle}");
+                }
+                Ok(Self::rotation(axis, angle.to_radians()))
+            }
+        }
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        matrix: Affine::IDENTITY,
+        inverse: Affine::IDENTITY,
+    };
+
+    pub const fn translation(by: Vec3) -> Self {
+        Self {
+            matrix: Affine {
+                linear: Mat3::IDENTITY,
+                translation: by,
+            },
+            inverse: Affine {
+                linear: Mat3::IDENTITY,
+                translation: Vec3::new(-by.x, -by.y, -by.z),
+            },
+        }
+    }
+
+    pub fn scaling(by: Vec3) -> Self {
+        Self {
+            matrix: Affine::linear(Mat3::diagonal(by)),
+            inverse: Affine::linear(Mat3::diagonal(Vec3::ONE / by)),
+        }
+    }
+
+    /// [Rotation matrix from axis and angle][1].
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Rotation_matrix#Rotation_matrix_from_axis_and_angle
+    pub fn rotation(axis: Vec3, angle: f64) -> Self {
+        let Vec3 { x, y, z } = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let one_minus_cos = 1.0 - cos;
+        let matrix = Mat3::from_rows([
+            Vec3::new(
+                cos + x * x * one_minus_cos,
+                x * y * one_minus_cos - z * sin,
+                x * z * one_minus_cos + y * sin,
+            ),
+            Vec3::new(
+                y * x * one_minus_cos + z * sin,
+                cos + y * y * one_minus_cos,
+                y * z * one_minus_cos - x * sin,
+            ),
+            Vec3::new(
+                z * x * one_minus_cos - y * sin,
+                z * y * one_minus_cos + x * sin,
+                cos + z * z * one_minus_cos,
+            ),
+        ]);
+        // Rotation matrix is orthogonal:
+        Self {
+            matrix: Affine::linear(matrix),
+            inverse: Affine::linear(matrix.transpose()),
+        }
+    }
+
+    /// Apply the other transformation after this one.
+    pub fn then(self, other: Self) -> Self {
+        Self {
+            matrix: other.matrix.after(self.matrix),
+            inverse: self.inverse.after(other.inverse),
+        }
+    }
+
+    #[inline]
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.matrix.apply_to_point(point)
+    }
+
+    /// Transform the normal with the inverse transpose matrix, so that it stays perpendicular
+    /// to the transformed surface.
+    ///
+    /// The result is **not** normalized.
+    #[inline]
+    pub fn transform_normal(&self, normal: Vec3) -> Vec3 {
+        self.inverse.linear.transpose() * normal
+    }
+
+    #[inline]
+    pub fn inverse_transform_point(&self, point: Vec3) -> Vec3 {
+        self.inverse.apply_to_point(point)
+    }
+
+    #[inline]
+    pub fn inverse_transform_vector(&self, vector: Vec3) -> Vec3 {
+        self.inverse.linear * vector
+    }
+
+    /// Get the box which encompasses all the transformed corners of the original box.
+    pub fn transform_aabb(&self, aabb: &Aabb) -> Aabb {
+        let (min, max) = (aabb.min_point, aabb.max_point);
+        let corners = [
+            Vec3::new(min.x, min.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(min.x, max.y, max.z),
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(max.x, max.y, max.z),
+        ]
+        .map(|corner| self.transform_point(corner));
+        Aabb {
+            min_point: corners.into_iter().reduce(Vec3::min).unwrap(),
+            max_point: corners.into_iter().reduce(Vec3::max).unwrap(),
+        }
+    }
+}
+
+impl Affine {
+    const IDENTITY: Self = Self::linear(Mat3::IDENTITY);
+
+    const fn linear(linear: Mat3) -> Self {
+        Self { linear, translation: Vec3::ZERO }
+    }
+
+    /// Apply this transformation after the other one.
+    fn after(self, other: Self) -> Self {
+        Self {
+            linear: self.linear * other.linear,
+            translation: self.apply_to_point(other.translation),
+        }
+    }
+
+    #[inline]
+    fn apply_to_point(&self, point: Vec3) -> Vec3 {
+        self.linear * point + self.translation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn rotate_then_translate_ok() {
+        let transform = Transform::rotation(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2)
+            .then(Transform::translation(Vec3::new(1.0, 2.0, 3.0)));
+        let point = transform.transform_point(Vec3::new(1.0, 0.0, 0.0));
+        assert!(point.abs_diff_eq(Vec3::new(1.0, 3.0, 3.0), 1e-12), "actual: {point}");
+        let point = transform.inverse_transform_point(point);
+        assert!(point.abs_diff_eq(Vec3::new(1.0, 0.0, 0.0), 1e-12), "actual: {point}");
+    }
+
+    #[test]
+    fn normal_stays_perpendicular_ok() {
+        let transform = Transform::scaling(Vec3::new(1.0, 2.0, 1.0));
+        let tangent = transform.transform_point(Vec3::new(1.0, -1.0, 0.0))
+            - transform.transform_point(Vec3::ZERO);
+        let normal = transform.transform_normal(Vec3::new(1.0, 1.0, 0.0));
+        assert!(tangent.dot(normal).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zero_scale_fails() {
+        let scale = |by| Transform::try_from(vec![Transformation::Scale { by }]);
+        assert!(scale(Vec3::new(1.0, -2.0, 3.0)).is_ok());
+        assert!(scale(Vec3::new(1.0, 0.0, 1.0)).is_err());
+        assert!(scale(Vec3::new(1.0, f64::INFINITY, 1.0)).is_err());
+    }
+
+    #[test]
+    fn zero_rotation_axis_fails() {
+        let rotate = |axis| Transform::try_from(Transformation::Rotate { axis, angle: 90.0 });
+        assert!(rotate(Vec3::new(0.0, 0.0, 1.0)).is_ok());
+        assert!(rotate(Vec3::ZERO).is_err());
+        assert!(rotate(Vec3::new(f64::NAN, 0.0, 1.0)).is_err());
+    }
+}
@@ -240,6 +240,19 @@ impl Vec3 {
         self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
     }
 
+    /// Build two unit vectors, which form an orthonormal basis along with this **unit** vector:
+    /// <https://graphics.pixar.com/library/OrthonormalB/paper.pdf>.
+    #[inline]
+    pub fn orthonormal_basis(self) -> (Self, Self) {
+        let sign = 1.0_f64.copysign(self.z);
+        let a = -1.0 / (sign + self.z);
+        let b = self.x * self.y * a;
+        (
+            Self::new(1.0 + sign * self.x * self.x * a, sign * b, -sign * self.x),
+            Self::new(b, sign + self.y * self.y * a, -self.y),
+        )
+    }
+
     /// Rotate the vector around another **unit** vector:
     /// <https://en.wikipedia.org/wiki/Rodrigues%27_rotation_formula>.
     #[inline]
@@ -11,3 +11,6 @@ pub const PLANCK: Quantity<-1, 2, 1, 0, 0> = Quantity(6.62607015e-34);
 /// <https://en.wikipedia.org/wiki/Stefan%E2%80%93Boltzmann_law>
 #[allow(dead_code)]
 pub const STEFAN_BOLTZMANN: Quantity<-3, 0, 1, -4, 0> = Quantity(5.670374419e-8);
+
+/// <https://en.wikipedia.org/wiki/Electronvolt>
+pub const ELECTRONVOLT: Energy = Quantity(1.602176634e-19);
@@ -1,2 +1,6 @@
+pub mod illuminant;
 pub mod material;
+pub mod microfacet;
+pub mod sky;
 pub mod spectrum;
+pub mod table;
@@ -0,0 +1,333 @@
+mod data;
+
+use std::f64::consts::PI;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::color::observer::Observer;
+use crate::color::xyz::XyzColor;
+use crate::physics::optics::table::{Extrapolation, Interpolation, Table};
+use crate::physics::units::*;
+use crate::prelude::*;
+use crate::scene::Exposure;
+
+/// [CIE standard illuminant][1], or a reference lamp.
+///
+/// [1]: https://en.wikipedia.org/wiki/Standard_illuminant
+#[derive(Copy, Clone, Deserialize, JsonSchema)]
+pub enum StandardIlluminant {
+    /// Noon daylight in Western and Northern Europe, 6504K.
+    D65,
+
+    /// Horizon light, 5003K, which is used in the graphic arts industry.
+    D50,
+
+    /// Incandescent tungsten-filament lighting, 2856K.
+    A,
+
+    /// Standard fluorescent lamps.
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+
+    /// Broadband fluorescent lamps.
+    F7,
+    F8,
+    F9,
+
+    /// Narrow tri-band fluorescent lamps.
+    F10,
+    F11,
+    F12,
+
+    /// Phosphor-converted blue LEDs of increasing correlated color temperature.
+    #[serde(alias = "LED-B1")]
+    LedB1,
+    #[serde(alias = "LED-B2")]
+    LedB2,
+    #[serde(alias = "LED-B3")]
+    LedB3,
+    #[serde(alias = "LED-B4")]
+    LedB4,
+    #[serde(alias = "LED-B5")]
+    LedB5,
+
+    /// Hybrid of the phosphor-converted and red LEDs.
+    #[serde(alias = "LED-BH1")]
+    LedBh1,
+
+    /// Mixture of the red, green, and blue LEDs.
+    #[serde(alias = "LED-RGB1")]
+    LedRgb1,
+
+    /// Phosphor-converted violet LEDs.
+    #[serde(alias = "LED-V1")]
+    LedV1,
+    #[serde(alias = "LED-V2")]
+    LedV2,
+}
+
+/// Absolute scale of an illuminant, whose data only defines the relative spectral distribution.
+#[derive(Copy, Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Normalization {
+    /// Luminance, in candelas per square meter, as perceived by the CIE 1931 2° observer.
+    Luminance { luminance: f64 },
+
+    /// Radiance over the visible spectrum, in watts per square meter per steradian.
+    ///
+    /// A flat Lambertian emitter of the area `A` then emits the radiant flux of `πA` times that.
+    Radiance { radiance: f64 },
+
+    /// Radiant flux, in watts, which gets emitted by a flat Lambertian emitter of the given area,
+    /// in square meters.
+    Flux { flux: f64, area: f64 },
+}
+
+/// Illuminant as it is written in a scene file.
+#[derive(Deserialize, JsonSchema)]
+pub struct IlluminantDefinition {
+    pub name: StandardIlluminant,
+
+    #[serde(flatten)]
+    pub normalization: Normalization,
+}
+
+/// [CIE daylight illuminant][1] as it is written in a scene file.
+///
+/// [1]: https://en.wikipedia.org/wiki/Standard_illuminant#Illuminant_series_D
+#[derive(Deserialize, JsonSchema)]
+pub struct DaylightDefinition {
+    /// Correlated color temperature, from 4000K to 25000K.
+    pub temperature: Temperature,
+
+    #[serde(flatten)]
+    pub normalization: Normalization,
+}
+
+/// Normalized spectral radiance of an illuminant.
+#[derive(Clone, Deserialize)]
+#[serde(try_from = "IlluminantDefinition")]
+pub struct Illuminant {
+    /// Relative spectral distribution.
+    table: Table,
+
+    /// Multiplier, which turns the relative values into the spectral radiance.
+    scale: f64,
+}
+
+/// Daylight, which is an [`Illuminant`] defined by its correlated color temperature.
+#[derive(Clone, Deserialize)]
+#[serde(try_from = "DaylightDefinition")]
+pub struct Daylight(Illuminant);
+
+impl TryFrom<IlluminantDefinition> for Illuminant {
+    type Error = anyhow::Error;
+
+    fn try_from(definition: IlluminantDefinition) -> Result<Self> {
+        let (start, values): (f64, &[f64]) = match definition.name {
+            StandardIlluminant::D65 => (300.0, &data::D65),
+            StandardIlluminant::D50 => (300.0, &data::D50),
+            StandardIlluminant::A => {
+                return Self::new(Self::illuminant_a(), definition.normalization);
+            }
+            StandardIlluminant::F1 => (380.0, &data::F1),
+            StandardIlluminant::F2 => (380.0, &data::F2),
+            StandardIlluminant::F3 => (380.0, &data::F3),
+            StandardIlluminant::F4 => (380.0, &data::F4),
+            StandardIlluminant::F5 => (380.0, &data::F5),
+            StandardIlluminant::F6 => (380.0, &data::F6),
+            StandardIlluminant::F7 => (380.0, &data::F7),
+            StandardIlluminant::F8 => (380.0, &data::F8),
+            StandardIlluminant::F9 => (380.0, &data::F9),
+            StandardIlluminant::F10 => (380.0, &data::F10),
+            StandardIlluminant::F11 => (380.0, &data::F11),
+            StandardIlluminant::F12 => (380.0, &data::F12),
+            StandardIlluminant::LedB1 => (380.0, &data::LED_B1),
+            StandardIlluminant::LedB2 => (380.0, &data::LED_B2),
+            StandardIlluminant::LedB3 => (380.0, &data::LED_B3),
+            StandardIlluminant::LedB4 => (380.0, &data::LED_B4),
+            StandardIlluminant::LedB5 => (380.0, &data::LED_B5),
+            StandardIlluminant::LedBh1 => (380.0, &data::LED_BH1),
+            StandardIlluminant::LedRgb1 => (380.0, &data::LED_RGB1),
+            StandardIlluminant::LedV1 => (380.0, &data::LED_V1),
+            StandardIlluminant::LedV2 => (380.0, &data::LED_V2),
+        };
+        Self::new(Self::new_table(start, values.to_vec())?, definition.normalization)
+    }
+}
+
+impl TryFrom<DaylightDefinition> for Daylight {
+    type Error = anyhow::Error;
+
+    /// Combine the daylight components, as per CIE 15:2004.
+    fn try_from(definition: DaylightDefinition) -> Result<Self> {
+        let temperature = definition.temperature.0;
+        let x = match temperature {
+            t if (4000.0..=7000.0).contains(&t) => {
+                -4.6070e9 / t.powi(3) + 2.9678e6 / t.powi(2) + 0.09911e3 / t + 0.244063
+            }
+            t if (7000.0..=25000.0).contains(&t) => {
+                -2.0064e9 / t.powi(3) + 1.9018e6 / t.powi(2) + 0.24748e3 / t + 0.237040
+            }
+            _ => bail!("the daylight temperature must be within 4000K and 25000K"),
+        };
+        let y = -3.0 * x * x + 2.870 * x - 0.275;
+        let (m1, m2) = Daylight::component_weights(x, y);
+        let values = (0..data::DAYLIGHT_S0.len())
+            .map(|i| data::DAYLIGHT_S0[i] + m1 * data::DAYLIGHT_S1[i] + m2 * data::DAYLIGHT_S2[i])
+            .collect();
+        let illuminant =
+            Illuminant::new(Illuminant::new_table(300.0, values)?, definition.normalization)?;
+        Ok(Self(illuminant))
+    }
+}
+
+impl Illuminant {
+    fn new(table: Table, normalization: Normalization) -> Result<Self> {
+        let scale = match normalization {
+            Normalization::Luminance { luminance } => {
+                let color =
+                    XyzColor::from_spectrum(|wavelength| table.at(wavelength), Observer::Cie1931);
+                luminance / (Exposure::LUMINOUS_EFFICACY * color.luminance())
+            }
+            Normalization::Radiance { radiance } => radiance / Self::radiance(&table),
+            Normalization::Flux { flux, area } => flux / (PI * area) / Self::radiance(&table),
+        };
+        if !scale.is_finite() {
+            bail!("the illuminant cannot be normalized");
+        }
+        Ok(Self { table, scale })
+    }
+
+    /// Integrated radiance of the relative distribution over the visible spectrum.
+    fn radiance(table: &Table) -> f64 {
+        (360..=830)
+            .map(|nanos| table.at(Length::from_nanos(nanos as f64)) * 1e-9)
+            .sum()
+    }
+
+    /// Table with the 5nm step, which is zero outside of its range.
+    fn new_table(start_nanos: f64, values: Vec<f64>) -> Result<Table> {
+        let wavelengths = (0..values.len())
+            .map(|i| (start_nanos + 5.0 * i as f64) * 1e-9)
+            .collect();
+        Table::new(wavelengths, values, Interpolation::Linear, Extrapolation::Zero)
+    }
+
+    /// [CIE standard illuminant A][1], which is defined by its formula.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Standard_illuminant#Illuminant_A
+    fn illuminant_a() -> Table {
+        let wavelengths: Vec<f64> = (300..=830)
+            .step_by(5)
+            .map(|nanos| nanos as f64 * 1e-9)
+            .collect();
+        let values = wavelengths
+            .iter()
+            .map(|wavelength| {
+                let nanos = wavelength * 1e9;
+                let c2: f64 = 1.435e7 / 2848.0;
+                100.0 * (560.0 / nanos).powi(5) * ((c2 / 560.0).exp() - 1.0)
+                    / ((c2 / nanos).exp() - 1.0)
+            })
+            .collect();
+        Table::new(wavelengths, values, Interpolation::Linear, Extrapolation::Zero)
+            .expect("the table is well-formed")
+    }
+
+    #[inline]
+    pub fn at(&self, wavelength: Length) -> SpectralFluxDensity {
+        Quantity::from(self.table.at(wavelength) * self.scale)
+    }
+}
+
+impl Daylight {
+    #[inline]
+    pub fn at(&self, wavelength: Length) -> SpectralFluxDensity {
+        self.0.at(wavelength)
+    }
+
+    /// Relative spectral distributions of the daylight components S0, S1, and S2.
+    pub fn components() -> [Table; 3] {
+        [data::DAYLIGHT_S0, data::DAYLIGHT_S1, data::DAYLIGHT_S2].map(|values| {
+            Illuminant::new_table(300.0, values.to_vec()).expect("the table is well-formed")
+        })
+    }
+
+    /// Weights of the S1 and S2 components for the daylight of the chromaticity.
+    pub fn component_weights(x: f64, y: f64) -> (f64, f64) {
+        let m = 0.0241 + 0.2562 * x - 0.7341 * y;
+        let m1 = (-1.3515 - 1.7703 * x + 5.9114 * y) / m;
+        let m2 = (0.0300 - 31.4424 * x + 30.0717 * y) / m;
+        (m1, m2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::physics::optics::material::emittance::Emittance;
+    use crate::physics::optics::material::property::Property;
+
+    fn xyz(emittance: &Emittance) -> XyzColor {
+        XyzColor::from_spectrum(|wavelength| emittance.at(wavelength).0, Observer::Cie1931)
+    }
+
+    /// Published CIE 1931 2° chromaticities.
+    #[test]
+    fn chromaticity_ok() {
+        for (toml, expected) in [
+            ("type = 'Illuminant'\nname = 'D65'\nluminance = 1", (0.31272, 0.32903)),
+            ("type = 'Illuminant'\nname = 'D50'\nluminance = 1", (0.34567, 0.35850)),
+            ("type = 'Illuminant'\nname = 'A'\nluminance = 1", (0.44757, 0.40745)),
+            ("type = 'Illuminant'\nname = 'F2'\nluminance = 1", (0.37208, 0.37529)),
+            ("type = 'Illuminant'\nname = 'F11'\nluminance = 1", (0.38052, 0.37713)),
+            ("type = 'Daylight'\ntemperature = 6504\nluminance = 1", (0.31272, 0.32903)),
+        ] {
+            let emittance: Emittance = toml::from_str(toml).unwrap();
+            let (x, y) = xyz(&emittance).chromaticity();
+            assert_abs_diff_eq!(x, expected.0, epsilon = 5e-4);
+            assert_abs_diff_eq!(y, expected.1, epsilon = 5e-4);
+        }
+    }
+
+    #[test]
+    fn normalization_ok() {
+        let emittance: Emittance =
+            toml::from_str("type = 'Illuminant'\nname = 'LED-B3'\nluminance = 250").unwrap();
+        assert_abs_diff_eq!(
+            Exposure::LUMINOUS_EFFICACY * xyz(&emittance).luminance(),
+            250.0,
+            epsilon = 1e-9
+        );
+
+        let emittance: Emittance =
+            toml::from_str("type = 'Daylight'\ntemperature = 5500\nradiance = 10").unwrap();
+        let radiance: f64 = (360..=830)
+            .map(|nanos| emittance.at(Length::from_nanos(nanos as f64)).0 * 1e-9)
+            .sum();
+        assert_abs_diff_eq!(radiance, 10.0, epsilon = 1e-9);
+
+        let emittance: Emittance =
+            toml::from_str("type = 'Illuminant'\nname = 'D65'\nflux = 100\narea = 2").unwrap();
+        let radiance: f64 = (360..=830)
+            .map(|nanos| emittance.at(Length::from_nanos(nanos as f64)).0 * 1e-9)
+            .sum();
+        assert_abs_diff_eq!(PI * 2.0 * radiance, 100.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn daylight_range_fails() {
+        assert!(
+            toml::from_str::<Emittance>("type = 'Daylight'\ntemperature = 3000\nluminance = 1")
+                .is_err()
+        );
+    }
+}
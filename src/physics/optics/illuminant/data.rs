@@ -0,0 +1,279 @@
+//! [CIE data tables](https://cie.co.at/data-tables), © International Commission on Illumination (CIE),
+//! licensed under CC BY-SA 4.0.
+
+#![allow(clippy::approx_constant)]
+
+/// Daylight component S0, 300–830nm with 5nm step.
+pub const DAYLIGHT_S0: [f64; 107] = [
+    0.04, 3.02, 6.0, 17.8, 29.6, 42.45, 55.3, 56.3, 57.3, 59.55, 61.8, 61.65, 61.5, 65.15, 68.8,
+    66.1, 63.4, 64.6, 65.8, 80.3, 94.8, 99.8, 104.8, 105.35, 105.9, 101.35, 96.8, 105.35, 113.9,
+    119.75, 125.6, 125.55, 125.5, 123.4, 121.3, 121.3, 121.3, 117.4, 113.5, 113.3, 113.1, 111.95,
+    110.8, 108.65, 106.5, 107.65, 108.8, 107.05, 105.3, 104.85, 104.4, 102.2, 100.0, 98.0, 96.0,
+    95.55, 95.1, 92.1, 89.1, 89.8, 90.5, 90.4, 90.3, 89.35, 88.4, 86.2, 84.0, 84.55, 85.1, 83.5,
+    81.9, 82.25, 82.6, 83.75, 84.9, 83.1, 81.3, 76.6, 71.9, 73.1, 74.3, 75.35, 76.4, 69.85, 63.3,
+    67.5, 71.7, 74.35, 77.0, 71.1, 65.2, 56.45, 47.7, 58.15, 68.6, 66.8, 65.0, 65.5, 66.0, 63.5,
+    61.0, 57.15, 53.3, 56.1, 58.9, 60.4, 61.9,
+];
+
+/// Daylight component S1, 300–830nm with 5nm step.
+pub const DAYLIGHT_S1: [f64; 107] = [
+    0.02, 2.26, 4.5, 13.45, 22.4, 32.2, 42.0, 41.3, 40.6, 41.1, 41.6, 39.8, 38.0, 40.2, 42.4,
+    40.45, 38.5, 36.75, 35.0, 39.2, 43.4, 44.85, 46.3, 45.1, 43.9, 40.5, 37.1, 36.9, 36.7, 36.3,
+    35.9, 34.25, 32.6, 30.25, 27.9, 26.1, 24.3, 22.2, 20.1, 18.15, 16.2, 14.7, 13.2, 10.9, 8.6,
+    7.35, 6.1, 5.15, 4.2, 3.05, 1.9, 0.95, 0.0, -0.8, -1.6, -2.55, -3.5, -3.5, -3.5, -4.65, -5.8,
+    -6.5, -7.2, -7.9, -8.6, -9.05, -9.5, -10.2, -10.9, -10.8, -10.7, -11.35, -12.0, -13.0, -14.0,
+    -13.8, -13.6, -12.8, -12.0, -12.65, -13.3, -13.1, -12.9, -11.75, -10.6, -11.1, -11.6, -11.9,
+    -12.2, -11.2, -10.2, -9.0, -7.8, -9.5, -11.2, -10.8, -10.4, -10.5, -10.6, -10.15, -9.7, -9.0,
+    -8.3, -8.8, -9.3, -9.55, -9.8,
+];
+
+/// Daylight component S2, 300–830nm with 5nm step.
+pub const DAYLIGHT_S2: [f64; 107] = [
+    0.0, 1.0, 2.0, 3.0, 4.0, 6.25, 8.5, 8.15, 7.8, 7.25, 6.7, 6.0, 5.3, 5.7, 6.1, 4.55, 3.0, 2.1,
+    1.2, 0.05, -1.1, -0.8, -0.5, -0.6, -0.7, -0.95, -1.2, -1.9, -2.6, -2.75, -2.9, -2.85, -2.8,
+    -2.7, -2.6, -2.6, -2.6, -2.2, -1.8, -1.65, -1.5, -1.4, -1.3, -1.25, -1.2, -1.1, -1.0, -0.75,
+    -0.5, -0.4, -0.3, -0.15, 0.0, 0.1, 0.2, 0.35, 0.5, 1.3, 2.1, 2.65, 3.2, 3.65, 4.1, 4.4, 4.7,
+    4.9, 5.1, 5.9, 6.7, 7.0, 7.3, 7.95, 8.6, 9.2, 9.8, 10.0, 10.2, 9.25, 8.3, 8.95, 9.6, 9.05, 8.5,
+    7.75, 7.0, 7.3, 7.6, 7.8, 8.0, 7.35, 6.7, 5.95, 5.2, 6.3, 7.4, 7.1, 6.8, 6.9, 7.0, 6.7, 6.4,
+    5.95, 5.5, 5.8, 6.1, 6.3, 6.5,
+];
+
+/// Standard illuminant D50, 300–830nm with 5nm step.
+pub const D50: [f64; 107] = [
+    0.01922, 1.03486, 2.0505, 4.91445, 7.7784, 11.263, 14.7475, 16.3478, 17.948, 19.4788, 21.0097,
+    22.4758, 23.9419, 25.4513, 26.9607, 25.7241, 24.4875, 27.179, 29.8706, 39.5894, 49.3081,
+    52.9104, 56.5128, 58.2733, 60.0338, 58.9256, 57.8175, 66.3212, 74.8249, 81.036, 87.2472,
+    88.9297, 90.6122, 90.9902, 91.3681, 93.2383, 95.1085, 93.5356, 91.9627, 93.8432, 95.7237,
+    96.1685, 96.6133, 96.8712, 97.129, 99.614, 102.099, 101.427, 100.755, 101.536, 102.317,
+    101.158, 100.0, 98.8675, 97.735, 98.3265, 98.918, 96.2084, 93.4988, 95.5933, 97.6878, 98.4784,
+    99.2691, 99.1553, 99.0415, 97.3816, 95.7218, 97.2895, 98.8572, 97.2622, 95.6672, 96.9285,
+    98.1898, 100.597, 103.003, 101.068, 99.133, 93.257, 87.3809, 89.4922, 91.6035, 92.246, 92.8886,
+    84.8715, 76.8544, 81.6828, 86.5112, 89.5455, 92.5798, 85.4048, 78.2299, 67.9608, 57.6918,
+    70.3074, 82.923, 80.5985, 78.274, 78.9142, 79.5544, 76.478, 73.4015, 68.6608, 63.9202, 67.3486,
+    70.777, 72.6094, 74.4417,
+];
+
+/// Standard illuminant D65, 300–830nm with 5nm step.
+pub const D65: [f64; 107] = [
+    0.0341, 1.6643, 3.2945, 11.7652, 20.236, 28.6447, 37.0535, 38.5011, 39.9488, 42.4302, 44.9117,
+    45.775, 46.6383, 49.3637, 52.0891, 51.0323, 49.9755, 52.3118, 54.6482, 68.7015, 82.7549,
+    87.1204, 91.486, 92.4589, 93.4318, 90.057, 86.6823, 95.7736, 104.865, 110.936, 117.008, 117.41,
+    117.812, 116.336, 114.861, 115.392, 115.923, 112.367, 108.811, 109.082, 109.354, 108.578,
+    107.802, 106.296, 104.79, 106.239, 107.689, 106.047, 104.405, 104.225, 104.046, 102.023, 100.0,
+    98.1671, 96.3342, 96.0611, 95.788, 92.2368, 88.6856, 89.3459, 90.0062, 89.8026, 89.5991,
+    88.6489, 87.6987, 85.4936, 83.2886, 83.4939, 83.6992, 81.863, 80.0268, 80.1207, 80.2146,
+    81.2462, 82.2778, 80.281, 78.2842, 74.0027, 69.7213, 70.6652, 71.6091, 72.979, 74.349, 67.9765,
+    61.604, 65.7448, 69.8856, 72.4863, 75.087, 69.3398, 63.5927, 55.0054, 46.4182, 56.6118,
+    66.8054, 65.0941, 63.3828, 63.8434, 64.304, 61.8779, 59.4519, 55.7054, 51.959, 54.6998,
+    57.4406, 58.8765, 60.3125,
+];
+
+/// Fluorescent illuminant F1, 380–780nm with 5nm step.
+pub const F1: [f64; 81] = [
+    1.87, 2.36, 2.94, 3.47, 5.17, 19.49, 6.13, 6.24, 7.01, 7.79, 8.56, 43.67, 16.94, 10.72, 11.35,
+    11.89, 12.37, 12.75, 13.0, 13.15, 13.23, 13.17, 13.13, 12.85, 12.52, 12.2, 11.83, 11.5, 11.22,
+    11.05, 11.03, 11.18, 11.53, 27.74, 17.05, 13.55, 14.33, 15.01, 15.52, 18.29, 19.55, 15.48,
+    14.91, 14.15, 13.22, 12.19, 11.12, 10.03, 8.95, 7.96, 7.02, 6.2, 5.42, 4.73, 4.15, 3.64, 3.2,
+    2.81, 2.47, 2.18, 1.93, 1.72, 1.67, 1.43, 1.29, 1.19, 1.08, 0.96, 0.88, 0.81, 0.77, 0.75, 0.73,
+    0.68, 0.69, 0.64, 0.68, 0.69, 0.61, 0.52, 0.43,
+];
+
+/// Fluorescent illuminant F2, 380–780nm with 5nm step.
+pub const F2: [f64; 81] = [
+    1.18, 1.48, 1.84, 2.15, 3.44, 15.69, 3.85, 3.74, 4.19, 4.62, 5.06, 34.98, 11.81, 6.27, 6.63,
+    6.93, 7.19, 7.4, 7.54, 7.62, 7.65, 7.62, 7.62, 7.45, 7.28, 7.15, 7.05, 7.04, 7.16, 7.47, 8.04,
+    8.88, 10.01, 24.88, 16.64, 14.59, 16.16, 17.56, 18.62, 21.47, 22.79, 19.29, 18.66, 17.73,
+    16.54, 15.21, 13.8, 12.36, 10.95, 9.65, 8.4, 7.32, 6.31, 5.43, 4.68, 4.02, 3.45, 2.96, 2.55,
+    2.19, 1.89, 1.64, 1.53, 1.27, 1.1, 0.99, 0.88, 0.76, 0.68, 0.61, 0.56, 0.54, 0.51, 0.47, 0.47,
+    0.43, 0.46, 0.47, 0.4, 0.33, 0.27,
+];
+
+/// Fluorescent illuminant F3, 380–780nm with 5nm step.
+pub const F3: [f64; 81] = [
+    0.82, 1.02, 1.26, 1.44, 2.57, 14.36, 2.7, 2.45, 2.73, 3.0, 3.28, 31.85, 9.47, 4.02, 4.25, 4.44,
+    4.59, 4.72, 4.8, 4.86, 4.87, 4.85, 4.88, 4.77, 4.67, 4.62, 4.62, 4.73, 4.99, 5.48, 6.25, 7.34,
+    8.78, 23.82, 16.14, 14.59, 16.63, 18.49, 19.95, 23.11, 24.69, 21.41, 20.85, 19.93, 18.67,
+    17.22, 15.65, 14.04, 12.45, 10.95, 9.51, 8.27, 7.11, 6.09, 5.22, 4.45, 3.8, 3.23, 2.75, 2.33,
+    1.99, 1.7, 1.55, 1.27, 1.09, 0.96, 0.83, 0.71, 0.62, 0.54, 0.49, 0.46, 0.43, 0.39, 0.39, 0.35,
+    0.38, 0.39, 0.33, 0.28, 0.21,
+];
+
+/// Fluorescent illuminant F4, 380–780nm with 5nm step.
+pub const F4: [f64; 81] = [
+    0.57, 0.7, 0.87, 0.98, 2.01, 13.75, 1.95, 1.59, 1.76, 1.93, 2.1, 30.28, 8.03, 2.55, 2.7, 2.82,
+    2.91, 2.99, 3.04, 3.08, 3.09, 3.09, 3.14, 3.06, 3.0, 2.98, 3.01, 3.14, 3.41, 3.9, 4.69, 5.81,
+    7.32, 22.59, 15.11, 13.88, 16.33, 18.68, 20.64, 24.28, 26.26, 23.28, 22.94, 22.14, 20.91,
+    19.43, 17.74, 16.0, 14.42, 12.56, 10.93, 9.52, 8.18, 7.01, 6.0, 5.11, 4.36, 3.69, 3.13, 2.64,
+    2.24, 1.91, 1.7, 1.39, 1.18, 1.03, 0.88, 0.74, 0.64, 0.54, 0.49, 0.46, 0.42, 0.37, 0.37, 0.33,
+    0.35, 0.36, 0.31, 0.26, 0.19,
+];
+
+/// Fluorescent illuminant F5, 380–780nm with 5nm step.
+pub const F5: [f64; 81] = [
+    1.87, 2.35, 2.92, 3.45, 5.1, 18.91, 6.0, 6.11, 6.85, 7.58, 8.31, 40.76, 16.06, 10.32, 10.91,
+    11.4, 11.83, 12.17, 12.4, 12.54, 12.58, 12.52, 12.47, 12.2, 11.89, 11.61, 11.33, 11.1, 10.96,
+    10.97, 11.16, 11.54, 12.12, 27.78, 17.73, 14.47, 15.2, 15.77, 16.1, 18.54, 19.5, 15.39, 14.64,
+    13.72, 12.69, 11.57, 10.45, 9.35, 8.29, 7.32, 6.41, 5.63, 4.9, 4.26, 3.72, 3.25, 2.83, 2.49,
+    2.19, 1.93, 1.71, 1.52, 1.48, 1.26, 1.13, 1.05, 0.96, 0.85, 0.78, 0.72, 0.68, 0.67, 0.65, 0.61,
+    0.62, 0.59, 0.62, 0.64, 0.55, 0.47, 0.4,
+];
+
+/// Fluorescent illuminant F6, 380–780nm with 5nm step.
+pub const F6: [f64; 81] = [
+    1.05, 1.31, 1.63, 1.9, 3.11, 14.8, 3.43, 3.3, 3.68, 4.07, 4.45, 32.61, 10.74, 5.48, 5.78, 6.03,
+    6.25, 6.41, 6.52, 6.58, 6.59, 6.56, 6.56, 6.42, 6.28, 6.2, 6.19, 6.3, 6.6, 7.12, 7.94, 9.07,
+    10.49, 25.22, 17.46, 15.63, 17.22, 18.53, 19.43, 21.97, 23.01, 19.41, 18.56, 17.42, 16.09,
+    14.64, 13.15, 11.68, 10.25, 8.95, 7.74, 6.69, 5.71, 4.87, 4.16, 3.55, 3.02, 2.57, 2.2, 1.87,
+    1.6, 1.37, 1.29, 1.05, 0.91, 0.81, 0.71, 0.61, 0.54, 0.48, 0.44, 0.43, 0.4, 0.37, 0.38, 0.35,
+    0.39, 0.41, 0.33, 0.26, 0.21,
+];
+
+/// Fluorescent illuminant F7, 380–780nm with 5nm step.
+pub const F7: [f64; 81] = [
+    2.56, 3.18, 3.84, 4.53, 6.15, 19.37, 7.37, 7.05, 7.71, 8.41, 9.15, 44.14, 17.52, 11.35, 12.0,
+    12.58, 13.08, 13.45, 13.71, 13.88, 13.95, 13.93, 13.82, 13.64, 13.43, 13.25, 13.08, 12.93,
+    12.78, 12.6, 12.44, 12.33, 12.26, 29.52, 17.05, 12.44, 12.58, 12.72, 12.83, 15.46, 16.75,
+    12.83, 12.67, 12.45, 12.19, 11.89, 11.6, 11.35, 11.12, 10.95, 10.76, 10.42, 10.11, 10.04,
+    10.02, 10.11, 9.87, 8.65, 7.27, 6.44, 5.83, 5.41, 5.04, 4.57, 4.12, 3.77, 3.46, 3.08, 2.73,
+    2.47, 2.25, 2.06, 1.9, 1.75, 1.62, 1.54, 1.45, 1.32, 1.17, 0.99, 0.81,
+];
+
+/// Fluorescent illuminant F8, 380–780nm with 5nm step.
+pub const F8: [f64; 81] = [
+    1.21, 1.5, 1.81, 2.13, 3.17, 13.08, 3.83, 3.45, 3.86, 4.42, 5.09, 34.1, 12.42, 7.68, 8.6, 9.46,
+    10.24, 10.84, 11.33, 11.71, 11.98, 12.17, 12.28, 12.32, 12.35, 12.44, 12.55, 12.68, 12.77,
+    12.72, 12.6, 12.43, 12.22, 28.96, 16.51, 11.79, 11.76, 11.77, 11.84, 14.61, 16.11, 12.34,
+    12.53, 12.72, 12.92, 13.12, 13.34, 13.61, 13.87, 14.07, 14.2, 14.16, 14.13, 14.34, 14.5, 14.46,
+    14.0, 12.58, 10.99, 9.98, 9.22, 8.62, 8.07, 7.39, 6.71, 6.16, 5.63, 5.03, 4.46, 4.02, 3.66,
+    3.36, 3.09, 2.85, 2.65, 2.51, 2.37, 2.15, 1.89, 1.61, 1.32,
+];
+
+/// Fluorescent illuminant F9, 380–780nm with 5nm step.
+pub const F9: [f64; 81] = [
+    0.9, 1.12, 1.36, 1.6, 2.59, 12.8, 3.05, 2.56, 2.86, 3.3, 3.82, 32.62, 10.77, 5.84, 6.57, 7.25,
+    7.86, 8.35, 8.75, 9.06, 9.31, 9.48, 9.61, 9.68, 9.74, 9.88, 10.04, 10.26, 10.48, 10.63, 10.78,
+    10.96, 11.18, 27.71, 16.29, 12.28, 12.74, 13.21, 13.65, 16.57, 18.14, 14.55, 14.65, 14.66,
+    14.61, 14.5, 14.39, 14.4, 14.47, 14.62, 14.72, 14.55, 14.4, 14.58, 14.88, 15.51, 15.47, 13.2,
+    10.57, 9.18, 8.25, 7.57, 7.03, 6.35, 5.72, 5.25, 4.8, 4.29, 3.8, 3.43, 3.12, 2.86, 2.64, 2.43,
+    2.26, 2.14, 2.02, 1.83, 1.61, 1.38, 1.12,
+];
+
+/// Fluorescent illuminant F10, 380–780nm with 5nm step.
+pub const F10: [f64; 81] = [
+    1.11, 0.8, 0.62, 0.57, 1.48, 12.16, 2.12, 2.7, 3.74, 5.14, 6.75, 34.39, 14.86, 10.4, 10.76,
+    10.67, 10.11, 9.27, 8.29, 7.29, 7.91, 16.64, 16.73, 10.44, 5.94, 3.34, 2.35, 1.88, 1.59, 1.47,
+    1.8, 5.71, 40.98, 73.69, 33.61, 8.24, 3.38, 2.47, 2.14, 4.86, 11.45, 14.79, 12.16, 8.97, 6.52,
+    8.31, 44.12, 34.55, 12.09, 12.15, 10.52, 4.43, 1.95, 2.19, 3.19, 2.77, 2.29, 2.0, 1.52, 1.35,
+    1.47, 1.79, 1.74, 1.02, 1.14, 3.32, 4.49, 2.05, 0.49, 0.24, 0.21, 0.21, 0.24, 0.24, 0.21, 0.17,
+    0.21, 0.22, 0.17, 0.12, 0.09,
+];
+
+/// Fluorescent illuminant F11, 380–780nm with 5nm step.
+pub const F11: [f64; 81] = [
+    0.91, 0.63, 0.46, 0.37, 1.29, 12.68, 1.59, 1.79, 2.46, 3.33, 4.49, 33.94, 12.13, 6.95, 7.19,
+    7.12, 6.72, 6.13, 5.46, 4.79, 5.66, 14.29, 14.96, 8.97, 4.72, 2.33, 1.47, 1.1, 0.89, 0.83,
+    1.18, 4.9, 39.59, 72.84, 32.61, 7.52, 2.83, 1.96, 1.67, 4.43, 11.28, 14.76, 12.73, 9.74, 7.33,
+    9.72, 55.27, 42.58, 13.18, 13.16, 12.26, 5.11, 2.07, 2.34, 3.58, 3.01, 2.48, 2.14, 1.54, 1.33,
+    1.46, 1.94, 2.0, 1.2, 1.35, 4.1, 5.58, 2.51, 0.57, 0.27, 0.23, 0.21, 0.24, 0.24, 0.2, 0.24,
+    0.32, 0.26, 0.16, 0.12, 0.09,
+];
+
+/// Fluorescent illuminant F12, 380–780nm with 5nm step.
+pub const F12: [f64; 81] = [
+    0.96, 0.64, 0.4, 0.33, 1.19, 12.48, 1.12, 0.94, 1.08, 1.37, 1.78, 29.05, 7.9, 2.65, 2.71, 2.65,
+    2.49, 2.33, 2.1, 1.91, 3.01, 10.83, 11.88, 6.88, 3.43, 1.49, 0.92, 0.71, 0.6, 0.63, 1.1, 4.56,
+    34.4, 65.4, 29.48, 7.16, 3.08, 2.47, 2.27, 5.09, 11.96, 15.32, 14.27, 11.86, 9.28, 12.31,
+    68.53, 53.02, 14.67, 14.38, 14.71, 6.46, 2.57, 2.75, 4.18, 3.44, 2.81, 2.42, 1.64, 1.36, 1.49,
+    2.14, 2.34, 1.42, 1.61, 5.04, 6.98, 3.19, 0.71, 0.3, 0.26, 0.23, 0.28, 0.28, 0.21, 0.17, 0.21,
+    0.19, 0.15, 0.1, 0.05,
+];
+
+/// LED illuminant LED-B1, 380–780nm with 5nm step.
+pub const LED_B1: [f64; 81] = [
+    0.0, 0.01, 0.01, 0.02, 0.04, 0.07, 0.15, 0.28, 0.53, 0.92, 1.54, 2.52, 4.16, 6.39, 7.89, 7.57,
+    6.25, 5.16, 4.37, 3.84, 3.7, 3.88, 4.26, 4.79, 5.41, 6.05, 6.7, 7.34, 7.98, 8.64, 9.31, 10.02,
+    10.76, 11.53, 12.38, 13.28, 14.23, 15.2, 16.2, 17.19, 18.14, 19.02, 19.78, 20.38, 20.8, 21.0,
+    20.97, 20.71, 20.23, 19.56, 18.76, 17.82, 16.78, 15.66, 14.49, 13.31, 12.15, 11.02, 9.95, 8.93,
+    7.98, 7.1, 6.3, 5.57, 4.89, 4.26, 3.72, 3.25, 2.83, 2.47, 2.15, 1.87, 1.63, 1.42, 1.25, 1.1,
+    0.97, 0.86, 0.76, 0.68, 0.61,
+];
+
+/// LED illuminant LED-B2, 380–780nm with 5nm step.
+pub const LED_B2: [f64; 81] = [
+    0.0, 0.0, 0.0, 0.01, 0.02, 0.05, 0.11, 0.24, 0.5, 0.96, 1.71, 2.82, 4.71, 7.87, 10.56, 10.24,
+    7.94, 6.25, 5.23, 4.36, 4.0, 4.17, 4.59, 5.21, 5.96, 6.72, 7.44, 8.11, 8.74, 9.39, 10.06,
+    10.75, 11.49, 12.25, 13.05, 13.86, 14.7, 15.5, 16.28, 17.0, 17.69, 18.31, 18.83, 19.24, 19.51,
+    19.59, 19.48, 19.19, 18.72, 18.1, 17.34, 16.47, 15.51, 14.49, 13.42, 12.33, 11.26, 10.24, 9.25,
+    8.3, 7.42, 6.6, 5.85, 5.17, 4.55, 3.99, 3.49, 3.04, 2.65, 2.31, 2.01, 1.75, 1.52, 1.32, 1.15,
+    1.0, 0.87, 0.76, 0.67, 0.59, 0.52,
+];
+
+/// LED illuminant LED-B3, 380–780nm with 5nm step.
+pub const LED_B3: [f64; 81] = [
+    0.0, 0.0, 0.01, 0.02, 0.05, 0.11, 0.25, 0.56, 1.2, 2.37, 4.24, 7.17, 12.11, 17.7, 18.87, 14.58,
+    9.9, 7.53, 6.02, 5.11, 5.17, 5.82, 6.77, 7.88, 8.94, 9.82, 10.52, 11.06, 11.51, 11.93, 12.37,
+    12.83, 13.31, 13.79, 14.26, 14.68, 15.08, 15.42, 15.72, 15.97, 16.18, 16.37, 16.5, 16.55, 16.5,
+    16.32, 16.0, 15.54, 14.96, 14.27, 13.48, 12.62, 11.71, 10.78, 9.85, 8.93, 8.04, 7.2, 6.42,
+    5.69, 5.02, 4.42, 3.88, 3.39, 2.95, 2.56, 2.22, 1.92, 1.65, 1.43, 1.23, 1.06, 0.91, 0.78, 0.68,
+    0.58, 0.5, 0.43, 0.37, 0.32, 0.28,
+];
+
+/// LED illuminant LED-B4, 380–780nm with 5nm step.
+pub const LED_B4: [f64; 81] = [
+    0.0, 0.0, 0.0, 0.01, 0.03, 0.08, 0.21, 0.54, 1.31, 2.75, 5.04, 8.31, 13.37, 20.61, 25.91,
+    24.12, 17.47, 11.9, 8.62, 6.34, 4.89, 4.25, 4.19, 4.7, 5.77, 7.27, 8.94, 10.6, 12.11, 13.36,
+    14.3, 15.02, 15.54, 15.9, 16.15, 16.33, 16.42, 16.44, 16.36, 16.2, 15.98, 15.7, 15.36, 14.93,
+    14.44, 13.86, 13.24, 12.57, 11.84, 11.12, 10.38, 9.65, 8.91, 8.19, 7.51, 6.86, 6.21, 5.6, 5.05,
+    4.54, 4.05, 3.62, 3.23, 2.87, 2.53, 2.24, 1.99, 1.76, 1.55, 1.37, 1.21, 1.07, 0.95, 0.85, 0.75,
+    0.67, 0.61, 0.55, 0.5, 0.45, 0.42,
+];
+
+/// LED illuminant LED-B5, 380–780nm with 5nm step.
+pub const LED_B5: [f64; 81] = [
+    0.0, 0.01, 0.01, 0.03, 0.07, 0.16, 0.35, 0.78, 1.67, 3.3, 5.94, 9.92, 16.15, 25.15, 32.34,
+    31.18, 23.46, 16.39, 12.08, 9.08, 7.21, 6.48, 6.49, 7.05, 8.1, 9.45, 10.88, 12.22, 13.38,
+    14.29, 14.98, 15.48, 15.81, 16.01, 16.1, 16.13, 16.1, 15.98, 15.77, 15.5, 15.15, 14.76, 14.31,
+    13.79, 13.22, 12.59, 11.91, 11.2, 10.47, 9.73, 9.0, 8.27, 7.57, 6.9, 6.25, 5.65, 5.08, 4.55,
+    4.06, 3.61, 3.22, 2.86, 2.54, 2.26, 2.0, 1.75, 1.54, 1.36, 1.2, 1.05, 0.93, 0.82, 0.72, 0.64,
+    0.56, 0.49, 0.44, 0.39, 0.35, 0.31, 0.27,
+];
+
+/// LED illuminant LED-BH1, 380–780nm with 5nm step.
+pub const LED_BH1: [f64; 81] = [
+    0.0, 0.01, 0.02, 0.04, 0.08, 0.17, 0.41, 0.93, 1.84, 3.05, 4.29, 5.69, 7.06, 7.91, 7.74, 6.62,
+    5.13, 3.85, 2.94, 2.34, 1.99, 1.92, 2.19, 2.87, 4.03, 5.64, 7.49, 9.25, 10.82, 12.11, 13.08,
+    13.66, 14.01, 14.19, 14.28, 14.27, 14.19, 14.03, 13.82, 13.59, 13.35, 13.2, 13.19, 13.46,
+    14.31, 16.15, 19.49, 25.07, 33.87, 40.75, 35.59, 21.59, 11.11, 7.27, 5.58, 4.64, 3.98, 3.48,
+    3.07, 2.73, 2.42, 2.15, 1.91, 1.7, 1.5, 1.33, 1.18, 1.05, 0.93, 0.83, 0.74, 0.65, 0.57, 0.51,
+    0.46, 0.41, 0.37, 0.34, 0.3, 0.28, 0.25,
+];
+
+/// LED illuminant LED-RGB1, 380–780nm with 5nm step.
+pub const LED_RGB1: [f64; 81] = [
+    0.0, 0.0, 0.0, 0.01, 0.02, 0.05, 0.11, 0.24, 0.5, 0.94, 1.6, 2.43, 3.38, 4.59, 5.84, 5.56, 4.0,
+    3.14, 2.8, 2.68, 3.03, 3.98, 5.53, 7.84, 10.96, 14.61, 18.01, 20.24, 20.57, 19.22, 16.93,
+    14.58, 12.61, 11.09, 9.93, 9.11, 8.6, 8.31, 8.21, 8.24, 8.42, 8.74, 9.25, 10.0, 11.1, 12.74,
+    15.35, 19.43, 26.01, 36.47, 51.1, 62.69, 52.07, 28.62, 15.5, 9.58, 6.54, 4.95, 4.01, 3.37,
+    2.89, 2.51, 2.18, 1.92, 1.67, 1.44, 1.25, 1.09, 0.93, 0.82, 0.71, 0.61, 0.53, 0.45, 0.4, 0.35,
+    0.3, 0.27, 0.24, 0.21, 0.17,
+];
+
+/// LED illuminant LED-V1, 380–780nm with 5nm step.
+pub const LED_V1: [f64; 81] = [
+    0.01, 0.03, 0.14, 0.61, 2.62, 8.43, 16.51, 17.9, 11.26, 5.94, 3.31, 2.25, 2.07, 2.29, 2.68,
+    3.15, 3.64, 4.18, 4.83, 5.57, 6.4, 7.27, 8.08, 8.73, 9.21, 9.54, 9.75, 9.91, 10.06, 10.26,
+    10.51, 10.82, 11.17, 11.55, 11.92, 12.3, 12.68, 13.07, 13.52, 14.06, 14.7, 15.46, 16.38, 17.39,
+    18.5, 19.51, 20.52, 21.52, 22.38, 23.07, 23.51, 23.71, 23.67, 23.35, 22.81, 22.01, 21.03,
+    19.91, 18.69, 17.39, 16.05, 14.69, 13.36, 12.04, 10.8, 9.62, 8.54, 7.57, 6.68, 5.86, 5.12,
+    4.46, 3.88, 3.37, 2.94, 2.54, 2.21, 1.9, 1.65, 1.44, 1.22,
+];
+
+/// LED illuminant LED-V2, 380–780nm with 5nm step.
+pub const LED_V2: [f64; 81] = [
+    0.01, 0.06, 0.26, 1.15, 4.47, 11.98, 19.89, 20.24, 14.39, 8.95, 5.78, 4.65, 4.86, 5.78, 6.97,
+    8.1, 9.05, 9.82, 10.52, 11.21, 11.87, 12.5, 12.98, 13.23, 13.3, 13.21, 13.05, 12.88, 12.74,
+    12.68, 12.7, 12.77, 12.91, 13.04, 13.16, 13.26, 13.35, 13.42, 13.52, 13.64, 13.83, 14.07,
+    14.42, 14.82, 15.26, 15.64, 16.03, 16.4, 16.67, 16.88, 16.93, 16.85, 16.62, 16.23, 15.72,
+    15.07, 14.31, 13.45, 12.57, 11.64, 10.69, 9.78, 8.87, 8.0, 7.17, 6.37, 5.65, 5.01, 4.42, 3.88,
+    3.39, 2.95, 2.58, 2.24, 1.95, 1.7, 1.47, 1.3, 1.1, 0.96, 0.84,
+];
@@ -1,19 +1,24 @@
 pub mod attenuation;
+pub mod coating;
 pub mod emittance;
 pub mod property;
 pub mod reflectance;
+pub mod scattering;
 pub mod transmittance;
 
 use schemars::JsonSchema;
 use serde::Deserialize;
 
+use self::coating::ThinFilm;
+use self::scattering::Scattering;
 use self::transmittance::Transmittance;
 use crate::physics::optics::material::emittance::Emittance;
-use crate::physics::optics::material::reflectance::Reflectance;
+use crate::physics::optics::material::reflectance::{Reflectance, ReflectanceDefinition};
 
 #[derive(Default, Deserialize, JsonSchema)]
 pub struct Material {
     #[serde(default)]
+    #[schemars(with = "Option<ReflectanceDefinition>")]
     pub reflectance: Option<Reflectance>, // TODO: make it a vector.
 
     #[serde(default)]
@@ -21,4 +26,20 @@ pub struct Material {
 
     #[serde(default)]
     pub emittance: Option<Emittance>, // TODO: make it a vector.
+
+    #[serde(default)]
+    pub coating: Option<ThinFilm>,
+
+    /// Volume scattering, which takes precedence over the other properties of a fog material.
+    #[serde(default)]
+    pub scattering: Option<Scattering>,
+}
+
+impl Material {
+    /// Scale of the density, which a fog of this material must sample its collisions with.
+    pub fn majorant_scale(&self) -> f64 {
+        self.scattering
+            .as_ref()
+            .map_or(1.0, Scattering::majorant_scale)
+    }
 }
@@ -3,6 +3,7 @@ use serde::Deserialize;
 
 use crate::physics::optics::material::property::Property;
 use crate::physics::optics::spectrum::lorentzian;
+use crate::physics::optics::table::{Table, TableDefinition};
 use crate::physics::units::*;
 
 /// Absorbs nothing by default.
@@ -30,6 +31,9 @@ pub enum Attenuation {
 
     /// Sum of the spectra.
     Sum { spectra: Vec<Attenuation> },
+
+    /// Measured spectrum.
+    Tabulated(#[schemars(with = "TableDefinition")] Table),
 }
 
 impl Default for Attenuation {
@@ -61,6 +65,8 @@ impl Property<Bare> for Attenuation {
                 .iter()
                 .map(|attenuation| attenuation.at(wavelength))
                 .sum(),
+
+            Self::Tabulated(table) => Bare::from(table.at(wavelength)),
         }
     }
 }
@@ -0,0 +1,261 @@
+use std::f64::consts::TAU;
+use std::ops::{Add, Div, Mul, Sub};
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::math::noise::value_noise;
+use crate::math::vec3::Vec3;
+use crate::physics::optics::material::property::Property;
+use crate::physics::optics::material::transmittance::refraction::AbsoluteRefractiveIndex;
+use crate::physics::units::*;
+
+/// [Thin-film][1] coating, such as a soap bubble, an oil slick, or an anti-reflection coating.
+///
+/// The reflectance of the coated surface follows the Airy formula for the waves,
+/// which get reflected inside the film multiple times.
+/// The substrate is the refracted medium of a transmittive surface, or the conductor
+/// of an opaque one. Other surfaces are not affected by the coating.
+///
+/// [1]: https://en.wikipedia.org/wiki/Thin-film_interference
+#[derive(Deserialize, JsonSchema)]
+pub struct ThinFilm {
+    pub thickness: FilmThickness,
+
+    #[serde(alias = "index")]
+    pub refractive_index: AbsoluteRefractiveIndex,
+}
+
+#[derive(Copy, Clone, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum FilmThickness {
+    Constant {
+        thickness: Length,
+    },
+
+    /// Thickness, which smoothly varies over the surface between the minimum and maximum.
+    Noise {
+        min: Length,
+        max: Length,
+
+        /// Typical size of the thickness variations, in meters.
+        scale: f64,
+    },
+}
+
+impl FilmThickness {
+    /// Get the film thickness at the given point of the surface.
+    pub fn at(&self, location: Vec3) -> Length {
+        match self {
+            Self::Constant { thickness } => *thickness,
+            Self::Noise { min, max, scale } => {
+                *min + (*max - *min) * Bare::from(value_noise(location / *scale))
+            }
+        }
+    }
+}
+
+impl ThinFilm {
+    /// Calculate the unpolarized reflectance of the coated surface.
+    ///
+    /// The substrate index may be complex, which is the case for a conductor.
+    pub fn reflectance(
+        &self,
+        location: Vec3,
+        wavelength: Length,
+        incident_index: f64,
+        substrate_index: (f64, f64),
+        cosine_theta: f64,
+    ) -> Bare {
+        let n1 = Complex(incident_index, 0.0);
+        let n2 = Complex(self.refractive_index.at(wavelength).0, 0.0);
+        let n3 = Complex(substrate_index.0, substrate_index.1);
+
+        // Cosines of the refraction angles, as per Snell's law:
+        let sine_squared = 1.0 - cosine_theta.clamp(0.0, 1.0).powi(2);
+        let cosine = |n: Complex| (Complex(1.0, 0.0) - (n1 / n).squared() * sine_squared).sqrt();
+        let c1 = Complex(cosine_theta.clamp(0.0, 1.0), 0.0);
+        let c2 = cosine(n2);
+        let c3 = cosine(n3);
+
+        // Phase difference of the successive reflections:
+        let thickness = self.thickness.at(location).0;
+        let phase = (n2 * c2 * (2.0 * TAU * thickness / wavelength.0)).exp_i();
+
+        let airy = |r12: Complex, r23: Complex| {
+            ((r12 + r23 * phase) / (Complex(1.0, 0.0) + r12 * r23 * phase)).norm_squared()
+        };
+        let s = airy(Self::amplitude_s(n1, c1, n2, c2), Self::amplitude_s(n2, c2, n3, c3));
+        let p = airy(Self::amplitude_p(n1, c1, n2, c2), Self::amplitude_p(n2, c2, n3, c3));
+        Bare::from((0.5 * (s + p)).clamp(0.0, 1.0))
+    }
+
+    /// Fresnel reflection amplitude for the s-polarization.
+    fn amplitude_s(n1: Complex, c1: Complex, n2: Complex, c2: Complex) -> Complex {
+        (n1 * c1 - n2 * c2) / (n1 * c1 + n2 * c2)
+    }
+
+    /// Fresnel reflection amplitude for the p-polarization.
+    fn amplitude_p(n1: Complex, c1: Complex, n2: Complex, c2: Complex) -> Complex {
+        (n2 * c1 - n1 * c2) / (n2 * c1 + n1 * c2)
+    }
+}
+
+/// Bare minimum of the complex arithmetic, which is needed for the Airy formula.
+#[derive(Copy, Clone)]
+struct Complex(f64, f64);
+
+impl Complex {
+    #[inline]
+    fn squared(self) -> Self {
+        self * self
+    }
+
+    #[inline]
+    fn norm_squared(self) -> f64 {
+        self.0 * self.0 + self.1 * self.1
+    }
+
+    /// Principal square root.
+    fn sqrt(self) -> Self {
+        let norm = self.norm_squared().sqrt();
+        let re = (0.5 * (norm + self.0)).max(0.0).sqrt();
+        let im = (0.5 * (norm - self.0)).max(0.0).sqrt();
+        Self(re, im.copysign(self.1))
+    }
+
+    /// Calculate `exp(i · self)`.
+    fn exp_i(self) -> Self {
+        let magnitude = (-self.1).exp();
+        let (sin, cos) = self.0.sin_cos();
+        Self(magnitude * cos, magnitude * sin)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0 - self.1 * rhs.1, self.0 * rhs.1 + self.1 * rhs.0)
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: f64) -> Self {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        let norm_squared = rhs.norm_squared();
+        Self(
+            (self.0 * rhs.0 + self.1 * rhs.1) / norm_squared,
+            (self.1 * rhs.0 - self.0 * rhs.1) / norm_squared,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::physics::optics::material::reflectance::conductor::{
+        Conductor,
+        ConductorDefinition,
+    };
+
+    fn new_film(thickness: Length, index: f64) -> ThinFilm {
+        ThinFilm {
+            thickness: FilmThickness::Constant { thickness },
+            refractive_index: AbsoluteRefractiveIndex::Constant { index: Bare::from(index) },
+        }
+    }
+
+    /// Without the film, it must match the bare Fresnel equations.
+    #[test]
+    fn zero_thickness_ok() {
+        let film = new_film(Length::ZERO, 1.33);
+        let wavelength = Length::from_nanos(550.0);
+        let reflectance = film.reflectance(Vec3::ZERO, wavelength, 1.0, (1.5, 0.0), 1.0);
+        assert_abs_diff_eq!(reflectance.0, 0.04, epsilon = 1e-12);
+
+        let cosine = 0.6;
+        let gold = Conductor::try_from(ConductorDefinition::Gold).unwrap();
+        let reflectance =
+            film.reflectance(Vec3::ZERO, wavelength, 1.0, gold.index_at(wavelength), cosine);
+        assert_abs_diff_eq!(reflectance.0, gold.reflectance(wavelength, cosine).0, epsilon = 1e-12,);
+    }
+
+    /// Quarter-wave coating with the geometric mean index cancels the reflection.
+    #[test]
+    fn anti_reflection_ok() {
+        let index = 1.5_f64.sqrt();
+        let film = new_film(Length::from_nanos(550.0 / 4.0 / index), index);
+        let reflectance = |nanos| {
+            film.reflectance(Vec3::ZERO, Length::from_nanos(nanos), 1.0, (1.5, 0.0), 1.0)
+                .0
+        };
+        assert_abs_diff_eq!(reflectance(550.0), 0.0, epsilon = 1e-12);
+        assert!(reflectance(400.0) > 0.002);
+    }
+
+    /// Soap film of half-wave thickness is transparent at the normal incidence,
+    /// and reflects in-between: that is where the iridescence comes from.
+    #[test]
+    fn soap_film_ok() {
+        let film = new_film(Length::from_nanos(550.0 / 2.0 / 1.33), 1.33);
+        let reflectance = |nanos| {
+            film.reflectance(Vec3::ZERO, Length::from_nanos(nanos), 1.0, (1.0, 0.0), 1.0)
+                .0
+        };
+        assert_abs_diff_eq!(reflectance(550.0), 0.0, epsilon = 1e-12);
+
+        // Quarter-wave at 1100nm, constructive interference: 4r² / (1 + r²)², r = 0.33 / 2.33:
+        let film = new_film(Length::from_nanos(1100.0 / 4.0 / 1.33), 1.33);
+        let r = 0.33 / 2.33_f64;
+        let expected = 4.0 * r * r / (1.0 + r * r).powi(2);
+        let reflectance =
+            film.reflectance(Vec3::ZERO, Length::from_nanos(1100.0), 1.0, (1.0, 0.0), 1.0);
+        assert_abs_diff_eq!(reflectance.0, expected, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn noise_thickness_ok() {
+        let thickness = FilmThickness::Noise {
+            min: Length::from_nanos(200.0),
+            max: Length::from_nanos(600.0),
+            scale: 0.01,
+        };
+        for i in 0..100 {
+            let thickness = thickness.at(Vec3::new(i as f64 * 0.003, 0.1, 0.2));
+            assert!((Length::from_nanos(200.0)..=Length::from_nanos(600.0)).contains(&thickness));
+        }
+    }
+}
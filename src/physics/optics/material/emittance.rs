@@ -2,8 +2,15 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 
 use crate::physics::consts::*;
+use crate::physics::optics::illuminant::{
+    Daylight,
+    DaylightDefinition,
+    Illuminant,
+    IlluminantDefinition,
+};
 use crate::physics::optics::material::property::Property;
 use crate::physics::optics::spectrum::lorentzian;
+use crate::physics::optics::table::{Table, TableDefinition};
 use crate::physics::units::*;
 
 #[derive(Deserialize, JsonSchema, Clone)]
@@ -30,6 +37,15 @@ pub enum Emittance {
         #[serde(alias = "fwhm")]
         full_width_at_half_maximum: Length,
     },
+
+    /// Measured spectral radiance, in watts per cubic meter.
+    Tabulated(#[schemars(with = "TableDefinition")] Table),
+
+    /// CIE standard illuminant or reference LED, normalized to the luminance or radiance.
+    Illuminant(#[schemars(with = "IlluminantDefinition")] Illuminant),
+
+    /// CIE daylight illuminant by the correlated color temperature.
+    Daylight(#[schemars(with = "DaylightDefinition")] Daylight),
 }
 
 impl Default for Emittance {
@@ -54,6 +70,10 @@ impl Property<SpectralFluxDensity> for Emittance {
                 maximum_at,
                 full_width_at_half_maximum,
             } => *maximum * lorentzian(wavelength, *maximum_at, *full_width_at_half_maximum),
+
+            Self::Tabulated(table) => Quantity::from(table.at(wavelength)),
+            Self::Illuminant(illuminant) => illuminant.at(wavelength),
+            Self::Daylight(daylight) => daylight.at(wavelength),
         }
     }
 }
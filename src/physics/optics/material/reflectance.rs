@@ -1,16 +1,74 @@
+pub mod conductor;
+
 use schemars::JsonSchema;
 use serde::Deserialize;
 
+use self::conductor::{Conductor, ConductorDefinition};
 use crate::physics::optics::material::attenuation::Attenuation;
+use crate::physics::optics::microfacet::Microfacets;
+use crate::prelude::*;
 
-#[derive(Deserialize, JsonSchema, Default)]
-pub struct Reflectance {
+/// Reflectance as it is written in a scene file.
+#[derive(Deserialize, JsonSchema)]
+pub struct ReflectanceDefinition {
     #[serde(default)]
     pub attenuation: Attenuation,
 
+    /// Legacy perturbation of the mirror direction, prefer the microfacets instead.
+    ///
+    /// It cannot be combined with the microfacets.
     #[serde(default)]
-    pub fuzz: Option<f64>, // TODO: this may relate to transmittance as well.
+    pub fuzz: Option<f64>,
 
     #[serde(default, alias = "diffuse")]
     pub diffusion: Option<f64>,
+
+    /// Conductor, whose Fresnel reflectance additionally attenuates the specular reflection.
+    #[serde(default)]
+    #[schemars(with = "Option<ConductorDefinition>")]
+    pub conductor: Option<Conductor>,
+
+    /// Rough specular reflection.
+    #[serde(default)]
+    pub microfacets: Option<Microfacets>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(try_from = "ReflectanceDefinition")]
+pub struct Reflectance {
+    pub attenuation: Attenuation,
+    pub fuzz: Option<f64>,
+    pub diffusion: Option<f64>,
+    pub conductor: Option<Conductor>,
+    pub microfacets: Option<Microfacets>,
+}
+
+impl TryFrom<ReflectanceDefinition> for Reflectance {
+    type Error = anyhow::Error;
+
+    fn try_from(definition: ReflectanceDefinition) -> Result<Self> {
+        if definition.fuzz.is_some() && definition.microfacets.is_some() {
+            bail!("the fuzz cannot be combined with the microfacets");
+        }
+        Ok(Self {
+            attenuation: definition.attenuation,
+            fuzz: definition.fuzz,
+            diffusion: definition.diffusion,
+            conductor: definition.conductor,
+            microfacets: definition.microfacets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzz_with_microfacets_fails() {
+        let microfacets = "microfacets = { distribution = 'Ggx', roughness = 0.5 }";
+        assert!(toml::from_str::<Reflectance>("fuzz = 0.1").is_ok());
+        assert!(toml::from_str::<Reflectance>(microfacets).is_ok());
+        assert!(toml::from_str::<Reflectance>(&format!("fuzz = 0.1\n{microfacets}")).is_err());
+    }
 }
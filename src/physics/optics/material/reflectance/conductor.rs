@@ -0,0 +1,213 @@
+mod data;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::physics::consts::*;
+use crate::physics::optics::material::property::Property;
+use crate::physics::optics::material::transmittance::refraction::AbsoluteRefractiveIndex;
+use crate::physics::optics::table::{Extrapolation, Interpolation, Table};
+use crate::physics::units::*;
+use crate::prelude::*;
+
+/// Electrical conductor as it is written in a scene file.
+#[derive(Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum ConductorDefinition {
+    Custom {
+        /// Real part of the refractive index.
+        n: AbsoluteRefractiveIndex,
+
+        /// Extinction coefficient, the imaginary part of the refractive index.
+        k: AbsoluteRefractiveIndex,
+    },
+
+    /// Peter B. Johnson and R. W. Christy, "Optical Constants of the Noble Metals",
+    /// Phys. Rev. B 6, 4370 (1972); <https://doi.org/10.1103/PhysRevB.6.4370>.
+    #[serde(alias = "Au")]
+    Gold,
+
+    /// Peter B. Johnson and R. W. Christy, "Optical Constants of the Noble Metals",
+    /// Phys. Rev. B 6, 4370 (1972); <https://doi.org/10.1103/PhysRevB.6.4370>.
+    #[serde(alias = "Ag")]
+    Silver,
+
+    /// The same copper data as in [PBRT](https://pbr-book.org/3ed-2018/Reflection_Models/Specular_Reflection_and_Transmission#FresnelReflectance).
+    #[serde(alias = "Cu")]
+    Copper,
+
+    /// Lorentz–Drude model after Aleksandar D. Rakić et al., "Optical properties of metallic films
+    /// for vertical-cavity optoelectronic devices", Appl. Opt. 37, 5271-5283 (1998);
+    /// <https://doi.org/10.1364/AO.37.005271>.
+    #[serde(alias = "Al", alias = "Aluminum")]
+    Aluminium,
+}
+
+/// Electrical conductor, which reflects according to its [complex refractive index][1] `n + ik`.
+///
+/// [1]: https://en.wikipedia.org/wiki/Refractive_index#Complex_refractive_index
+#[derive(Deserialize)]
+#[serde(try_from = "ConductorDefinition")]
+pub enum Conductor {
+    Custom {
+        n: AbsoluteRefractiveIndex,
+        k: AbsoluteRefractiveIndex,
+    },
+
+    /// Measured `n` and `k`, which get clamped outside of the table.
+    Tabulated { n: Table, k: Table },
+
+    /// [Lorentz–Drude model][1] of aluminium.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Lorentz_oscillator_model
+    Aluminium,
+}
+
+impl TryFrom<ConductorDefinition> for Conductor {
+    type Error = anyhow::Error;
+
+    fn try_from(definition: ConductorDefinition) -> Result<Self> {
+        let data: &[[f64; 3]] = match definition {
+            ConductorDefinition::Custom { n, k } => return Ok(Self::Custom { n, k }),
+            ConductorDefinition::Gold => &data::GOLD,
+            ConductorDefinition::Silver => &data::SILVER,
+            ConductorDefinition::Copper => &data::COPPER,
+            ConductorDefinition::Aluminium => return Ok(Self::Aluminium),
+        };
+        let wavelengths: Vec<f64> = data.iter().map(|[nanos, _, _]| nanos * 1e-9).collect();
+        let [n, k] = [1, 2].map(|i| {
+            let values = data.iter().map(|row| row[i]).collect();
+            Table::new(wavelengths.clone(), values, Interpolation::Linear, Extrapolation::Clamp)
+        });
+        Ok(Self::Tabulated { n: n?, k: k? })
+    }
+}
+
+impl Conductor {
+    /// Get the complex refractive index at the given wavelength.
+    pub fn index_at(&self, wavelength: Length) -> (f64, f64) {
+        match self {
+            Self::Custom { n, k } => (n.at(wavelength).0, k.at(wavelength).0),
+            Self::Tabulated { n, k } => (n.at(wavelength), k.at(wavelength)),
+            Self::Aluminium => Self::lorentz_drude(
+                data::ALUMINIUM_PLASMA_ENERGY,
+                &data::ALUMINIUM_OSCILLATORS,
+                wavelength,
+            ),
+        }
+    }
+
+    /// Calculate the unpolarized reflectance by the full [Fresnel equations][1]
+    /// for the conductor in vacuum.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Fresnel_equations#Complex_amplitude_reflection_and_transmission_coefficients
+    pub fn reflectance(&self, wavelength: Length, cosine_theta: f64) -> Bare {
+        let (n, k) = self.index_at(wavelength);
+        Bare::from(Self::fresnel(n, k, cosine_theta))
+    }
+
+    /// See also: <https://seblagarde.wordpress.com/2013/04/29/memo-on-fresnel-equations/>.
+    fn fresnel(n: f64, k: f64, cosine_theta: f64) -> f64 {
+        let cosine_squared = cosine_theta.clamp(0.0, 1.0).powi(2);
+        let sine_squared = 1.0 - cosine_squared;
+        let t0 = n * n - k * k - sine_squared;
+        let a2_plus_b2 = (t0 * t0 + 4.0 * n * n * k * k).sqrt();
+        let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+
+        let t1 = a2_plus_b2 + cosine_squared;
+        let t2 = 2.0 * cosine_squared.sqrt() * a;
+        let r_s = (t1 - t2) / (t1 + t2);
+
+        let t3 = cosine_squared * a2_plus_b2 + sine_squared * sine_squared;
+        let t4 = t2 * sine_squared;
+        let r_p = r_s * (t3 - t4) / (t3 + t4);
+
+        0.5 * (r_s + r_p)
+    }
+
+    /// Complex refractive index of the [Lorentz–Drude model][1] with the energies in electronvolts.
+    ///
+    /// The permittivity is `1 + Σ fⱼωₚ² / (ωⱼ² - ω² - iωΓⱼ)`, where the free electrons have
+    /// the zero resonance energy.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Lorentz_oscillator_model
+    fn lorentz_drude(
+        plasma_energy: f64,
+        oscillators: &[[f64; 3]],
+        wavelength: Length,
+    ) -> (f64, f64) {
+        let energy = (PLANCK * LIGHT_SPEED / wavelength / ELECTRONVOLT).0;
+        let (re, im) =
+            oscillators
+                .iter()
+                .fold((1.0, 0.0), |(re, im), [strength, damping, resonance]| {
+                    let numerator = strength * plasma_energy * plasma_energy;
+                    let denominator_re = resonance * resonance - energy * energy;
+                    let denominator_im = -energy * damping;
+                    let norm = denominator_re * denominator_re + denominator_im * denominator_im;
+                    (re + numerator * denominator_re / norm, im - numerator * denominator_im / norm)
+                });
+
+        // Principal square root of the permittivity:
+        let modulus = re.hypot(im);
+        ((0.5 * (modulus + re)).sqrt(), (0.5 * (modulus - re)).max(0.0).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    /// Without the extinction, it must match the dielectric Fresnel equations.
+    #[test]
+    fn dielectric_ok() {
+        assert_abs_diff_eq!(Conductor::fresnel(1.5, 0.0, 1.0), 0.04, epsilon = 1e-12);
+        assert_abs_diff_eq!(Conductor::fresnel(1.5, 0.0, 0.0), 1.0, epsilon = 1e-12);
+
+        // 45°, the s- and p-reflectances are 0.0920 and 0.0085:
+        let cosine = std::f64::consts::FRAC_1_SQRT_2;
+        assert_abs_diff_eq!(Conductor::fresnel(1.5, 0.0, cosine), 0.05023, epsilon = 1e-4);
+    }
+
+    /// Known normal-incidence reflectances.
+    #[test]
+    fn metals_ok() {
+        let reflectance = |definition: ConductorDefinition, nanos: f64| {
+            let conductor = Conductor::try_from(definition).unwrap();
+            conductor.reflectance(Length::from_nanos(nanos), 1.0).0
+        };
+
+        // Gold is yellow:
+        assert!(reflectance(ConductorDefinition::Gold, 450.0) < 0.45);
+        assert!(reflectance(ConductorDefinition::Gold, 650.0) > 0.9);
+
+        // Silver is bright and neutral:
+        assert!(reflectance(ConductorDefinition::Silver, 450.0) > 0.9);
+        assert!(reflectance(ConductorDefinition::Silver, 650.0) > 0.95);
+
+        // Copper is reddish:
+        assert!(reflectance(ConductorDefinition::Copper, 450.0) < 0.6);
+        assert!(reflectance(ConductorDefinition::Copper, 650.0) > 0.9);
+
+        // Aluminium is about 92% at 550nm:
+        assert_abs_diff_eq!(
+            reflectance(ConductorDefinition::Aluminium, 550.0),
+            0.915,
+            epsilon = 0.002
+        );
+    }
+
+    /// The tables are interpolated between the points, and clamped outside of them.
+    #[test]
+    fn tabulated_ok() {
+        let gold = Conductor::try_from(ConductorDefinition::Gold).unwrap();
+        let (n, k) = gold.index_at(Length::from_nanos(548.6));
+        assert_abs_diff_eq!(n, 0.43, epsilon = 1e-12);
+        assert_abs_diff_eq!(k, 2.455, epsilon = 1e-12);
+        let (n, k) = gold.index_at(Length::from_nanos(1000.0));
+        assert_abs_diff_eq!(n, 0.17, epsilon = 1e-12);
+        assert_abs_diff_eq!(k, 5.663, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,115 @@
+//! Optical constants of the metals.
+//!
+//! The tables list the wavelength in nanometers, `n`, and `k`.
+
+/// Peter B. Johnson and R. W. Christy, "Optical Constants of the Noble Metals",
+/// Phys. Rev. B 6, 4370 (1972); <https://doi.org/10.1103/PhysRevB.6.4370>.
+pub const GOLD: [[f64; 3]; 18] = [
+    [354.2, 1.5, 1.866],
+    [367.9, 1.48, 1.895],
+    [381.5, 1.46, 1.933],
+    [397.4, 1.47, 1.952],
+    [413.3, 1.46, 1.958],
+    [430.5, 1.45, 1.948],
+    [450.9, 1.38, 1.914],
+    [471.4, 1.31, 1.849],
+    [495.9, 1.04, 1.833],
+    [520.9, 0.62, 2.081],
+    [548.6, 0.43, 2.455],
+    [582.1, 0.29, 2.863],
+    [616.8, 0.21, 3.272],
+    [659.5, 0.14, 3.697],
+    [704.5, 0.13, 4.103],
+    [756.0, 0.14, 4.542],
+    [821.1, 0.16, 5.083],
+    [892.0, 0.17, 5.663],
+];
+
+/// Peter B. Johnson and R. W. Christy, "Optical Constants of the Noble Metals",
+/// Phys. Rev. B 6, 4370 (1972); <https://doi.org/10.1103/PhysRevB.6.4370>.
+pub const SILVER: [[f64; 3]; 18] = [
+    [354.2, 0.1, 1.419],
+    [367.9, 0.07, 1.657],
+    [381.5, 0.05, 1.864],
+    [397.4, 0.05, 2.07],
+    [413.3, 0.05, 2.275],
+    [430.5, 0.04, 2.462],
+    [450.9, 0.04, 2.657],
+    [471.4, 0.05, 2.869],
+    [495.9, 0.05, 3.093],
+    [520.9, 0.05, 3.324],
+    [548.6, 0.06, 3.586],
+    [582.1, 0.05, 3.858],
+    [616.8, 0.06, 4.152],
+    [659.5, 0.05, 4.483],
+    [704.5, 0.04, 4.838],
+    [756.0, 0.03, 5.242],
+    [821.1, 0.04, 5.727],
+    [892.0, 0.04, 6.312],
+];
+
+/// The same copper data as in [PBRT](https://pbr-book.org/3ed-2018/Reflection_Models/Specular_Reflection_and_Transmission#FresnelReflectance).
+pub const COPPER: [[f64; 3]; 43] = [
+    [354.2, 1.29, 1.916],
+    [359.4, 1.2816, 1.9317],
+    [364.7, 1.27, 1.95],
+    [370.1, 1.2491, 1.9724],
+    [375.7, 1.225, 2.015],
+    [381.5, 1.2, 2.1216],
+    [387.5, 1.18, 2.21],
+    [393.6, 1.1744, 2.1772],
+    [399.9, 1.175, 2.13],
+    [406.5, 1.1775, 2.1601],
+    [413.3, 1.18, 2.21],
+    [420.3, 1.1781, 2.2499],
+    [427.5, 1.175, 2.289],
+    [435.0, 1.1728, 2.326],
+    [442.8, 1.17, 2.362],
+    [450.9, 1.1653, 2.3976],
+    [459.2, 1.16, 2.433],
+    [467.9, 1.1553, 2.4692],
+    [476.9, 1.15, 2.504],
+    [486.2, 1.1428, 2.5359],
+    [495.9, 1.135, 2.564],
+    [506.1, 1.1316, 2.5896],
+    [516.6, 1.12, 2.605],
+    [527.6, 1.0924, 2.5956],
+    [539.1, 1.04, 2.583],
+    [551.0, 0.9504, 2.5765],
+    [563.6, 0.826, 2.599],
+    [576.7, 0.6459, 2.6781],
+    [590.4, 0.468, 2.809],
+    [604.8, 0.3513, 3.0107],
+    [619.9, 0.272, 3.24],
+    [635.8, 0.2308, 3.4582],
+    [652.5, 0.214, 3.67],
+    [670.2, 0.2092, 3.8631],
+    [688.8, 0.213, 4.05],
+    [708.5, 0.2162, 4.2396],
+    [729.3, 0.223, 4.43],
+    [751.4, 0.2365, 4.6196],
+    [774.9, 0.25, 4.817],
+    [799.9, 0.2542, 5.0341],
+    [826.6, 0.26, 5.26],
+    [855.1, 0.28, 5.4856],
+    [885.6, 0.3, 5.717],
+];
+
+/// Lorentz–Drude model of aluminium: the plasma energy, in electronvolts.
+///
+/// Aleksandar D. Rakić, Aleksandra B. Djurišić, Jovan M. Elazar, and Marian L. Majewski,
+/// "Optical properties of metallic films for vertical-cavity optoelectronic devices",
+/// Appl. Opt. 37, 5271-5283 (1998); <https://doi.org/10.1364/AO.37.005271>.
+pub const ALUMINIUM_PLASMA_ENERGY: f64 = 14.98;
+
+/// Lorentz–Drude model of aluminium: the strength, damping, and resonance energy,
+/// in electronvolts, of the free electrons followed by the oscillators.
+///
+/// From the same paper as [`ALUMINIUM_PLASMA_ENERGY`].
+pub const ALUMINIUM_OSCILLATORS: [[f64; 3]; 5] = [
+    [0.523, 0.047, 0.0],
+    [0.227, 0.333, 0.162],
+    [0.050, 0.312, 1.544],
+    [0.166, 1.351, 1.808],
+    [0.030, 3.382, 3.473],
+];
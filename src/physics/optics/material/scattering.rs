@@ -0,0 +1,208 @@
+use std::f64::consts::TAU;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::physics::optics::material::attenuation::Attenuation;
+use crate::physics::units::*;
+
+/// Scattering inside a participating medium, such as fog.
+#[derive(Deserialize, JsonSchema)]
+pub struct Scattering {
+    /// Single-scattering albedo: the fraction of the light, which gets scattered rather than absorbed.
+    #[serde(default)]
+    pub albedo: Attenuation,
+
+    #[serde(alias = "phase")]
+    pub phase_function: PhaseFunction,
+}
+
+/// [Phase function][1]: the angular distribution of the scattered light.
+///
+/// [1]: https://www.pbr-book.org/3ed-2018/Volume_Scattering/Phase_Functions
+#[derive(Copy, Clone, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum PhaseFunction {
+    /// Scatter uniformly in all the directions.
+    Isotropic,
+
+    /// [Henyey–Greenstein phase function][1].
+    ///
+    /// [1]: https://www.astro.umd.edu/~jph/HG_note.pdf
+    HenyeyGreenstein {
+        /// Asymmetry parameter, the mean cosine of the scattering angle:
+        /// positive values scatter forward, negative values – backward.
+        g: f64,
+    },
+
+    /// [Rayleigh scattering][1] by the particles, which are much smaller than the wavelength.
+    ///
+    /// The scattering coefficient is proportional to `λ⁻⁴`, and the fog density is the one at 550nm.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Rayleigh_scattering
+    Rayleigh,
+
+    /// Cornette–Shanks approximation of the [Mie scattering][1] by the larger particles,
+    /// such as the haze and the water droplets.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Mie_scattering
+    Mie {
+        #[serde(default = "PhaseFunction::default_mie_g")]
+        g: f64,
+    },
+}
+
+impl PhaseFunction {
+    pub const fn default_mie_g() -> f64 {
+        0.76
+    }
+}
+
+impl Scattering {
+    /// The shortest wavelength, which gets traced.
+    const RAYLEIGH_MIN_WAVELENGTH: Length = Quantity::from_nanos(360.0);
+    const RAYLEIGH_REFERENCE_WAVELENGTH: Length = Quantity::from_nanos(550.0);
+
+    /// Maximal density over the spectrum, relative to the nominal one.
+    ///
+    /// The media sample the collisions with this density, and then the tracer
+    /// rejects them as per [`Scattering::density_ratio`], which is the delta tracking over the spectrum.
+    pub fn majorant_scale(&self) -> f64 {
+        match self.phase_function {
+            PhaseFunction::Rayleigh => (Self::RAYLEIGH_REFERENCE_WAVELENGTH
+                / Self::RAYLEIGH_MIN_WAVELENGTH)
+                .0
+                .powi(4),
+            _ => 1.0,
+        }
+    }
+
+    /// Ratio of the actual density at the wavelength to the majorant one.
+    pub fn density_ratio(&self, wavelength: Length) -> f64 {
+        match self.phase_function {
+            PhaseFunction::Rayleigh => (Self::RAYLEIGH_MIN_WAVELENGTH / wavelength)
+                .0
+                .powi(4)
+                .min(1.0),
+            _ => 1.0,
+        }
+    }
+
+    /// Sample the scattered direction for the given propagation direction,
+    /// and return it along with the sample weight.
+    pub fn sample(&self, direction: Vec3, sample: Vec2) -> (Vec3, f64) {
+        let (cosine, weight) = self.phase_function.sample_cosine(sample.x);
+        let sine = (1.0 - cosine * cosine).max(0.0).sqrt();
+        let (phi_sin, phi_cos) = (TAU * sample.y).sin_cos();
+        let (tangent, bitangent) = direction.orthonormal_basis();
+        let scattered = sine * phi_cos * tangent + sine * phi_sin * bitangent + cosine * direction;
+        (scattered.normalize(), weight)
+    }
+}
+
+impl PhaseFunction {
+    /// Sample the cosine of the scattering angle, and return it along with the sample weight,
+    /// which is the ratio of the phase function to the sampling density.
+    fn sample_cosine(&self, sample: f64) -> (f64, f64) {
+        match *self {
+            Self::Isotropic => (1.0 - 2.0 * sample, 1.0),
+
+            Self::HenyeyGreenstein { g } => (Self::sample_henyey_greenstein(g, sample), 1.0),
+
+            Self::Rayleigh => {
+                // Solve the cubic CDF by Cardano's formula:
+                let a = 4.0 * sample - 2.0;
+                let root = (a * a + 1.0).sqrt();
+                ((a + root).cbrt() + (a - root).cbrt(), 1.0)
+            }
+
+            Self::Mie { g } => {
+                // Sample Henyey–Greenstein, and correct for the Cornette–Shanks shape:
+                let cosine = Self::sample_henyey_greenstein(g, sample);
+                (cosine, 1.5 * (1.0 + cosine * cosine) / (2.0 + g * g))
+            }
+        }
+    }
+
+    fn sample_henyey_greenstein(g: f64, sample: f64) -> f64 {
+        if g.abs() < 1e-3 {
+            return 1.0 - 2.0 * sample;
+        }
+        let term = (1.0 - g * g) / (1.0 - g + 2.0 * g * sample);
+        ((1.0 + g * g - term * term) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+
+    /// Phase function value for the cosine of the scattering angle, per steradian.
+    #[cfg(test)]
+    fn at(&self, cosine: f64) -> f64 {
+        use std::f64::consts::PI;
+
+        match *self {
+            Self::Isotropic => 1.0 / (4.0 * PI),
+            Self::HenyeyGreenstein { g } => {
+                (1.0 - g * g) / (4.0 * PI * (1.0 + g * g - 2.0 * g * cosine).powf(1.5))
+            }
+            Self::Rayleigh => 3.0 / (16.0 * PI) * (1.0 + cosine * cosine),
+            Self::Mie { g } => {
+                3.0 * (1.0 - g * g) * (1.0 + cosine * cosine)
+                    / (8.0 * PI * (2.0 + g * g) * (1.0 + g * g - 2.0 * g * cosine).powf(1.5))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::math::sequence::{RandomSequence, Sequence};
+
+    /// The sampled cosines must reproduce the first two moments of the phase function.
+    #[test]
+    fn sample_ok() {
+        for phase_function in [
+            PhaseFunction::Isotropic,
+            PhaseFunction::HenyeyGreenstein { g: 0.6 },
+            PhaseFunction::HenyeyGreenstein { g: -0.3 },
+            PhaseFunction::Rayleigh,
+            PhaseFunction::Mie { g: 0.76 },
+        ] {
+            let n_steps = 100_000;
+            let (mut norm, mut mean, mut mean_squared) = (0.0, 0.0, 0.0);
+            for i in 0..n_steps {
+                let cosine = -1.0 + 2.0 * (i as f64 + 0.5) / n_steps as f64;
+                let value = TAU * phase_function.at(cosine) * 2.0 / n_steps as f64;
+                norm += value;
+                mean += cosine * value;
+                mean_squared += cosine * cosine * value;
+            }
+            assert_abs_diff_eq!(norm, 1.0, epsilon = 1e-6);
+
+            let mut sequence = RandomSequence::with_seed(42);
+            let n_samples = 200_000;
+            let (mut actual_mean, mut actual_mean_squared) = (0.0, 0.0);
+            for _ in 0..n_samples {
+                let (cosine, weight) = phase_function.sample_cosine(sequence.next());
+                actual_mean += cosine * weight / n_samples as f64;
+                actual_mean_squared += cosine * cosine * weight / n_samples as f64;
+            }
+            assert_abs_diff_eq!(actual_mean, mean, epsilon = 5e-3);
+            assert_abs_diff_eq!(actual_mean_squared, mean_squared, epsilon = 5e-3);
+        }
+    }
+
+    #[test]
+    fn rayleigh_density_ok() {
+        let scattering = Scattering {
+            albedo: Attenuation::default(),
+            phase_function: PhaseFunction::Rayleigh,
+        };
+        let ratio = |nanos| scattering.density_ratio(Length::from_nanos(nanos));
+        assert_abs_diff_eq!(ratio(360.0), 1.0);
+        assert_abs_diff_eq!(ratio(720.0), 1.0 / 16.0);
+        assert_abs_diff_eq!(scattering.majorant_scale() * ratio(550.0), 1.0, epsilon = 1e-12);
+    }
+}
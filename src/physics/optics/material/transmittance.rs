@@ -5,6 +5,7 @@ use serde::Deserialize;
 
 use self::refraction::AbsoluteRefractiveIndex;
 use crate::physics::optics::material::property::Property;
+use crate::physics::optics::microfacet::Microfacets;
 use crate::physics::units::*;
 
 #[derive(Deserialize, JsonSchema)]
@@ -21,9 +22,16 @@ pub struct Transmittance {
 
     /// [Attenuation coefficient][1].
     ///
+    /// It only absorbs the light. For a scattering interior, put a `BoundedFog` of the same shape
+    /// inside, and let its scattering albedo account for the absorption instead.
+    ///
     /// [1]: https://en.wikipedia.org/wiki/Attenuation_coefficient
     #[serde(alias = "attenuation")]
     pub attenuation_coefficient: AttenuationCoefficient,
+
+    /// Rough, or frosted, surface, which scatters both the refraction and the reflection.
+    #[serde(default)]
+    pub microfacets: Option<Microfacets>,
 }
 
 #[derive(Copy, Clone, Deserialize, JsonSchema)]
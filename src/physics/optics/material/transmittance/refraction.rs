@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 
 use crate::physics::optics::material::property::Property;
+use crate::physics::optics::table::{Table, TableDefinition};
 use crate::physics::units::*;
 
 /// Absolute refraction index.
@@ -30,6 +31,16 @@ pub enum AbsoluteRefractiveIndex {
         d: Quantity<0, 6, 0, 0, 0>,
     },
 
+    /// [Sellmeier equation][1] with three terms: `n² = 1 + Σ Bᵢλ² / (λ² - Cᵢ)`.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Sellmeier_equation
+    Sellmeier {
+        b: [Bare; 3],
+
+        /// Resonance wavelengths squared, in square meters.
+        c: [Quantity<0, 2, 0, 0, 0>; 3],
+    },
+
     /// Alexey N. Bashkatov and Elina A. Genina
     /// "Water refractive index in dependence on temperature and wavelength: a simple approximation",
     /// Proc. SPIE 5068, Saratov Fall Meeting 2002: Optical Technologies in Biophysics and Medicine IV,
@@ -38,11 +49,44 @@ pub enum AbsoluteRefractiveIndex {
 
     /// [Fused quartz][1], fused silica or quartz glass.
     ///
-    /// TODO: Sellmeier equation.
+    /// I. H. Malitson, "Interspecimen Comparison of the Refractive Index of Fused Silica",
+    /// J. Opt. Soc. Am. 55, 1205-1209 (1965); <https://doi.org/10.1364/JOSA.55.001205>.
     ///
     /// [1]: https://en.wikipedia.org/wiki/Fused_quartz
     #[serde(alias = "FusedSilica", alias = "QuartzGlass")]
     FusedQuartz,
+
+    /// Schott N-BK7 [borosilicate crown glass][1], as per the Schott optical glass catalogue.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Borosilicate_glass
+    #[serde(alias = "BK7", alias = "N-BK7")]
+    Bk7,
+
+    /// Schott F2 flint glass, as per the Schott optical glass catalogue.
+    F2,
+
+    /// Schott SF11 dense flint glass, as per the Schott optical glass catalogue.
+    #[serde(alias = "SF11")]
+    Sf11,
+
+    /// [Sapphire][1], for the ordinary ray.
+    ///
+    /// I. H. Malitson, "Refraction and Dispersion of Synthetic Sapphire",
+    /// J. Opt. Soc. Am. 52, 1377-1379 (1962); <https://doi.org/10.1364/JOSA.52.001377>.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Sapphire
+    Sapphire,
+
+    /// [Diamond][1].
+    ///
+    /// F. Peter, "Über Brechungsindizes und Absorptionskonstanten des Diamanten zwischen 644 und 226 mμ",
+    /// Z. Phys. 15, 358-368 (1923); <https://doi.org/10.1007/BF01330487>.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Diamond
+    Diamond,
+
+    /// Measured refractive index.
+    Tabulated(#[schemars(with = "TableDefinition")] Table),
 }
 
 /// Refractive index of vacuum.
@@ -54,9 +98,69 @@ impl Default for AbsoluteRefractiveIndex {
 }
 
 impl AbsoluteRefractiveIndex {
-    const FUSED_QUARTZ: Self = Self::Cauchy2 {
-        a: Quantity(1.4580),
-        b: Quantity(3.54e-15),
+    const BK7: Self = Self::Sellmeier {
+        b: [
+            Quantity(1.03961212),
+            Quantity(0.231792344),
+            Quantity(1.01046945),
+        ],
+        c: [
+            Quantity(6.00069867e-15),
+            Quantity(2.00179144e-14),
+            Quantity(1.03560653e-10),
+        ],
+    };
+    const DIAMOND: Self = Self::Sellmeier {
+        b: [Quantity(0.3306), Quantity(4.3356), Quantity::ZERO],
+        c: [Quantity(3.0625e-14), Quantity(1.1236e-14), Quantity::ZERO],
+    };
+    const FUSED_QUARTZ: Self = Self::Sellmeier {
+        b: [
+            Quantity(0.6961663),
+            Quantity(0.4079426),
+            Quantity(0.8974794),
+        ],
+        c: [
+            Quantity(4.679148e-15),
+            Quantity(1.3512063e-14),
+            Quantity(9.7934003e-11),
+        ],
+    };
+    const SAPPHIRE: Self = Self::Sellmeier {
+        b: [
+            Quantity(1.4313493),
+            Quantity(0.65054713),
+            Quantity(5.3414021),
+        ],
+        c: [
+            Quantity(5.2799261e-15),
+            Quantity(1.42382647e-14),
+            Quantity(3.25017834e-10),
+        ],
+    };
+    const SCHOTT_F2: Self = Self::Sellmeier {
+        b: [
+            Quantity(1.34533359),
+            Quantity(0.209073176),
+            Quantity(0.937357162),
+        ],
+        c: [
+            Quantity(9.97743871e-15),
+            Quantity(4.70450767e-14),
+            Quantity(1.11886764e-10),
+        ],
+    };
+    const SF11: Self = Self::Sellmeier {
+        b: [
+            Quantity(1.73759695),
+            Quantity(0.313747346),
+            Quantity(1.89878101),
+        ],
+        c: [
+            Quantity(1.3188707e-14),
+            Quantity(6.23068142e-14),
+            Quantity(1.55236290e-10),
+        ],
     };
     const VACUUM: Self = Self::Constant { index: Quantity::ONE };
     const WATER: Self = Self::Cauchy4 {
@@ -81,9 +185,31 @@ impl Property<Bare> for AbsoluteRefractiveIndex {
                     + *d / wavelength.sextic()
             }
 
+            Self::Sellmeier { b, c } => {
+                let wavelength_squared = wavelength.squared();
+                let squared: Bare = b
+                    .iter()
+                    .zip(c)
+                    .map(|(b, c)| *b * wavelength_squared / (wavelength_squared - *c))
+                    .sum();
+                Bare::from((1.0 + squared.0).sqrt())
+            }
+
             Self::Water => Self::WATER.at(wavelength),
 
             Self::FusedQuartz => Self::FUSED_QUARTZ.at(wavelength),
+
+            Self::Bk7 => Self::BK7.at(wavelength),
+
+            Self::F2 => Self::SCHOTT_F2.at(wavelength),
+
+            Self::Sf11 => Self::SF11.at(wavelength),
+
+            Self::Sapphire => Self::SAPPHIRE.at(wavelength),
+
+            Self::Diamond => Self::DIAMOND.at(wavelength),
+
+            Self::Tabulated(table) => Bare::from(table.at(wavelength)),
         }
     }
 }
@@ -110,3 +236,32 @@ impl RelativeRefractiveIndex {
         r0 + (Bare::from(1.0) - r0) * (Bare::from(1.0) - cosine_theta_1).quintic()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    /// Catalogue indices at the helium d-line, 587.56nm, and the mercury e-line, 546.07nm.
+    #[test]
+    fn glass_ok() {
+        for (index, expected_d, expected_e) in [
+            (AbsoluteRefractiveIndex::FusedQuartz, 1.45846, 1.46008),
+            (AbsoluteRefractiveIndex::Bk7, 1.51680, 1.51872),
+            (AbsoluteRefractiveIndex::F2, 1.62004, 1.62408),
+            (AbsoluteRefractiveIndex::Sf11, 1.78472, 1.79190),
+        ] {
+            assert_abs_diff_eq!(index.at(Length::from_nanos(587.56)).0, expected_d, epsilon = 1e-4);
+            assert_abs_diff_eq!(index.at(Length::from_nanos(546.07)).0, expected_e, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn crystal_ok() {
+        let sapphire = AbsoluteRefractiveIndex::Sapphire.at(Length::from_nanos(589.3));
+        assert_abs_diff_eq!(sapphire.0, 1.7682, epsilon = 5e-4);
+        let diamond = AbsoluteRefractiveIndex::Diamond.at(Length::from_nanos(589.3));
+        assert_abs_diff_eq!(diamond.0, 2.4175, epsilon = 1e-3);
+    }
+}
@@ -0,0 +1,352 @@
+use std::f64::consts::{PI, TAU};
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// Rough surface, which consists of the microscopic perfectly specular facets.
+///
+/// The scattered directions are sampled from the [distribution of the visible normals][1],
+/// and weighted by the height-correlated Smith shadowing-masking function.
+///
+/// [1]: https://jcgt.org/published/0007/04/01/
+#[derive(Copy, Clone, Deserialize, JsonSchema)]
+pub struct Microfacets {
+    #[serde(default)]
+    pub distribution: Distribution,
+
+    /// Perceptual roughness, from `0` (smooth) to `1`.
+    ///
+    /// The distribution width `α` is the squared roughness.
+    pub roughness: f64,
+}
+
+/// Microfacet normal distribution.
+#[derive(Copy, Clone, Default, Deserialize, JsonSchema)]
+pub enum Distribution {
+    /// [GGX][1], also known as Trowbridge–Reitz, which has the longer tails.
+    ///
+    /// [1]: https://www.cs.cornell.edu/~srm/publications/EGSR07-btdf.pdf
+    #[default]
+    #[serde(alias = "GGX", alias = "TrowbridgeReitz")]
+    Ggx,
+
+    /// [Beckmann distribution][1] of the Gaussian surface slopes.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Specular_highlight#Beckmann_distribution
+    Beckmann,
+}
+
+impl Microfacets {
+    /// Sample a microfacet normal, which is visible from the `outgoing` direction.
+    ///
+    /// Both the macro-surface `normal` and the `outgoing` direction must be normalized,
+    /// and point to the same side.
+    pub fn sample_normal(&self, normal: Vec3, outgoing: Vec3, sample: Vec2) -> Vec3 {
+        let (tangent, bitangent) = normal.orthonormal_basis();
+        let local = Vec3::new(outgoing.dot(tangent), outgoing.dot(bitangent), outgoing.dot(normal));
+        let sampled = match self.distribution {
+            Distribution::Ggx => self.sample_ggx(local, sample),
+            Distribution::Beckmann => self.sample_beckmann(local, sample),
+        };
+        (sampled.x * tangent + sampled.y * bitangent + sampled.z * normal).normalize()
+    }
+
+    /// Path weight of a visible normal sample: `G₂(o, i) / G₁(o)`.
+    ///
+    /// The Fresnel term is not included, and the cosines are taken against the macro-surface normal.
+    /// It never exceeds one, and so the scattering never creates energy.
+    pub fn weight(&self, cosine_outgoing: f64, cosine_incoming: f64) -> f64 {
+        let lambda_outgoing = self.lambda(cosine_outgoing);
+        (1.0 + lambda_outgoing) / (1.0 + lambda_outgoing + self.lambda(cosine_incoming.abs()))
+    }
+
+    #[inline]
+    fn alpha(&self) -> f64 {
+        // Keep it away from zero, where the distributions degenerate:
+        self.roughness.powi(2).max(1e-4)
+    }
+
+    /// Smith auxiliary function `Λ` for the direction with the given cosine to the normal.
+    fn lambda(&self, cosine: f64) -> f64 {
+        let cosine = cosine.clamp(1e-9, 1.0);
+        let tangent = (1.0 - cosine * cosine).sqrt() / cosine;
+        match self.distribution {
+            Distribution::Ggx => 0.5 * ((1.0 + (self.alpha() * tangent).powi(2)).sqrt() - 1.0),
+            Distribution::Beckmann => {
+                let a = 1.0 / (self.alpha() * tangent);
+                if a >= 1.6 {
+                    0.0
+                } else {
+                    ((1.0 - 1.259 * a + 0.396 * a * a) / (3.535 * a + 2.181 * a * a)).max(0.0)
+                }
+            }
+        }
+    }
+
+    /// Eric Heitz, "Sampling the GGX Distribution of Visible Normals",
+    /// JCGT 7(4), 2018; <https://jcgt.org/published/0007/04/01/>.
+    fn sample_ggx(&self, outgoing: Vec3, sample: Vec2) -> Vec3 {
+        let alpha = self.alpha();
+
+        // Stretch the view, so that the distribution becomes the hemisphere:
+        let outgoing = Vec3::new(alpha * outgoing.x, alpha * outgoing.y, outgoing.z).normalize();
+
+        let length_squared = outgoing.x * outgoing.x + outgoing.y * outgoing.y;
+        let tangent = if length_squared > 0.0 {
+            Vec3::new(-outgoing.y, outgoing.x, 0.0) / length_squared.sqrt()
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let bitangent = outgoing.cross(tangent);
+
+        // Sample the projected area:
+        let radius = sample.x.sqrt();
+        let (phi_sin, phi_cos) = (TAU * sample.y).sin_cos();
+        let t1 = radius * phi_cos;
+        let s = 0.5 * (1.0 + outgoing.z);
+        let t2 = (1.0 - s) * (1.0 - t1 * t1).sqrt() + s * radius * phi_sin;
+        let normal =
+            t1 * tangent + t2 * bitangent + (1.0 - t1 * t1 - t2 * t2).max(0.0).sqrt() * outgoing;
+
+        // Unstretch back:
+        Vec3::new(alpha * normal.x, alpha * normal.y, normal.z.max(0.0)).normalize()
+    }
+
+    /// Beckmann visible normal sampling, as per [PBRT][1]:
+    /// Eric Heitz and Eugene d'Eon, "Importance Sampling Microfacet-Based BSDFs using
+    /// the Distribution of Visible Normals", Computer Graphics Forum 33(4), 2014.
+    ///
+    /// [1]: https://pbr-book.org/3ed-2018/Reflection_Models/Microfacet_Models
+    fn sample_beckmann(&self, outgoing: Vec3, sample: Vec2) -> Vec3 {
+        let alpha = self.alpha();
+        let outgoing = Vec3::new(alpha * outgoing.x, alpha * outgoing.y, outgoing.z).normalize();
+        let (slope_x, slope_y) = Self::sample_beckmann_slopes(outgoing.z, sample);
+
+        // Rotate to the azimuth of the outgoing direction, and unstretch:
+        let length = (outgoing.x * outgoing.x + outgoing.y * outgoing.y).sqrt();
+        let (phi_cos, phi_sin) = if length > 0.0 {
+            (outgoing.x / length, outgoing.y / length)
+        } else {
+            (1.0, 0.0)
+        };
+        let slope_x_rotated = phi_cos * slope_x - phi_sin * slope_y;
+        let slope_y_rotated = phi_sin * slope_x + phi_cos * slope_y;
+        Vec3::new(-alpha * slope_x_rotated, -alpha * slope_y_rotated, 1.0).normalize()
+    }
+
+    /// Sample the visible slopes of the unit Beckmann distribution,
+    /// for the view in the XZ-plane with the given cosine.
+    fn sample_beckmann_slopes(cosine: f64, sample: Vec2) -> (f64, f64) {
+        if cosine > 0.9999 {
+            // Normal incidence:
+            let radius = (-(1.0 - sample.x).ln()).sqrt();
+            let (phi_sin, phi_cos) = (TAU * sample.y).sin_cos();
+            return (radius * phi_cos, radius * phi_sin);
+        }
+
+        let sine = (1.0 - cosine * cosine).max(0.0).sqrt();
+        let tangent = sine / cosine;
+        let cotangent = 1.0 / tangent;
+        let theta = cosine.acos();
+
+        // Search the X-slope by Newton–Raphson iterations, falling back to the bisection:
+        let mut a = -1.0;
+        let mut c = erf(cotangent);
+        let sample_x = sample.x.max(1e-6);
+        let fit = 1.0 + theta * (-0.876 + theta * (0.4265 - 0.0594 * theta));
+        let mut b = c - (1.0 + c) * (1.0 - sample_x).powf(fit);
+        let normalization = 1.0 / (1.0 + c + tangent * (-cotangent * cotangent).exp() / PI.sqrt());
+        for _ in 0..10 {
+            if !(a..=c).contains(&b) {
+                b = 0.5 * (a + c);
+            }
+            let inverse_erf = erf_inverse(b);
+            let value = normalization
+                * (1.0 + b + tangent * (-inverse_erf * inverse_erf).exp() / PI.sqrt())
+                - sample_x;
+            if value.abs() < 1e-5 {
+                break;
+            }
+            if value > 0.0 {
+                c = b;
+            } else {
+                a = b;
+            }
+            let derivative = normalization * (1.0 - inverse_erf * tangent);
+            b -= value / derivative;
+        }
+
+        (erf_inverse(b), erf_inverse(2.0 * sample.y.max(1e-6) - 1.0))
+    }
+}
+
+/// [Error function][1], Abramowitz and Stegun approximation 7.1.26.
+///
+/// [1]: https://en.wikipedia.org/wiki/Error_function
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let polynomial = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    (1.0 - polynomial * (-x * x).exp()).copysign(x)
+}
+
+/// Inverse of the error function: Mike Giles, "Approximating the erfinv function".
+fn erf_inverse(x: f64) -> f64 {
+    let x = x.clamp(-0.99999, 0.99999);
+    let w = -((1.0 - x) * (1.0 + x)).ln();
+    let p = if w < 5.0 {
+        let w = w - 2.5;
+        [
+            3.43273939e-07,
+            -3.5233877e-06,
+            -4.39150654e-06,
+            0.00021858087,
+            -0.00125372503,
+            -0.00417768164,
+            0.246640727,
+            1.50140941,
+        ]
+        .into_iter()
+        .fold(2.81022636e-08, |p, coefficient| coefficient + p * w)
+    } else {
+        let w = w.sqrt() - 3.0;
+        [
+            0.000100950558,
+            0.00134934322,
+            -0.00367342844,
+            0.00573950773,
+            -0.0076224613,
+            0.00943887047,
+            1.00167406,
+            2.83297682,
+        ]
+        .into_iter()
+        .fold(-0.000200214257, |p, coefficient| coefficient + p * w)
+    };
+    p * x
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::math::sequence::{RandomSequence, Sequence};
+
+    const NORMAL: Vec3 = Vec3::new(0.0, 0.0, 1.0);
+
+    /// Normal distribution function `D(m)`.
+    fn density(microfacets: &Microfacets, cosine: f64) -> f64 {
+        let alpha_squared = microfacets.alpha().powi(2);
+        let cosine_squared = cosine * cosine;
+        let tangent_squared = (1.0 - cosine_squared) / cosine_squared;
+        match microfacets.distribution {
+            Distribution::Ggx => {
+                alpha_squared
+                    / (PI
+                        * cosine_squared
+                        * cosine_squared
+                        * (alpha_squared + tangent_squared).powi(2))
+            }
+            Distribution::Beckmann => {
+                (-tangent_squared / alpha_squared).exp()
+                    / (PI * alpha_squared * cosine_squared * cosine_squared)
+            }
+        }
+    }
+
+    const fn all_microfacets() -> [Microfacets; 2] {
+        [
+            Microfacets {
+                distribution: Distribution::Ggx,
+                roughness: 0.6,
+            },
+            Microfacets {
+                distribution: Distribution::Beckmann,
+                roughness: 0.6,
+            },
+        ]
+    }
+
+    #[test]
+    fn erf_ok() {
+        assert_abs_diff_eq!(erf(0.5), 0.520_499_877_8, epsilon = 1e-6);
+        assert_abs_diff_eq!(erf(-1.0), -0.842_700_792_9, epsilon = 1e-6);
+        for x in [-0.9, -0.3, 0.0, 0.4, 0.95] {
+            assert_abs_diff_eq!(erf(erf_inverse(x)), x, epsilon = 1e-6);
+        }
+    }
+
+    /// The sampled normals must follow `G₁(o) max(0, o·m) D(m) / (o·n)`,
+    /// which is checked by the mean `m·n` against the numerical integration.
+    #[test]
+    fn visible_normals_ok() {
+        let outgoing = Vec3::new(0.6, 0.0, 0.8);
+        for microfacets in all_microfacets() {
+            let masking = 1.0 / (1.0 + microfacets.lambda(outgoing.z));
+
+            let (n_theta, n_phi) = (400, 400);
+            let mut expected = 0.0;
+            let mut total = 0.0;
+            for i in 0..n_theta {
+                let theta = (i as f64 + 0.5) / n_theta as f64 * PI / 2.0;
+                for j in 0..n_phi {
+                    let phi = (j as f64 + 0.5) / n_phi as f64 * TAU;
+                    let normal =
+                        Vec3::new(theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos());
+                    let area = theta.sin() * (PI / 2.0 / n_theta as f64) * (TAU / n_phi as f64);
+                    let pdf =
+                        masking * outgoing.dot(normal).max(0.0) * density(&microfacets, normal.z)
+                            / outgoing.z;
+                    total += pdf * area;
+                    expected += normal.z * pdf * area;
+                }
+            }
+            assert_abs_diff_eq!(total, 1.0, epsilon = 2e-3);
+
+            let mut sequence = RandomSequence::with_seed(42);
+            let n_samples = 100_000;
+            let actual = (0..n_samples)
+                .map(|_| {
+                    microfacets
+                        .sample_normal(NORMAL, outgoing, sequence.next())
+                        .z
+                })
+                .sum::<f64>()
+                / n_samples as f64;
+            assert_abs_diff_eq!(actual, expected, epsilon = 5e-3);
+        }
+    }
+
+    /// White furnace: an ideal mirror may lose, but must never gain energy,
+    /// and it must not lose much when it is nearly smooth.
+    #[test]
+    fn energy_conservation_ok() {
+        let outgoing = Vec3::new(0.0, 0.6, 0.8);
+        for distribution in [Distribution::Ggx, Distribution::Beckmann] {
+            for (roughness, min_albedo) in [(0.1, 0.99), (0.5, 0.85), (1.0, 0.3)] {
+                let microfacets = Microfacets { distribution, roughness };
+                let mut sequence = RandomSequence::with_seed(42);
+                let n_samples = 10_000;
+                let albedo = (0..n_samples)
+                    .map(|_| {
+                        let normal = microfacets.sample_normal(NORMAL, outgoing, sequence.next());
+                        let incoming = (-outgoing).reflect_about(normal);
+                        if incoming.z <= 0.0 {
+                            return 0.0;
+                        }
+                        let weight = microfacets.weight(outgoing.z, incoming.z);
+                        assert!(weight <= 1.0);
+                        weight
+                    })
+                    .sum::<f64>()
+                    / n_samples as f64;
+                assert!((min_albedo..=1.0).contains(&albedo), "albedo = {albedo}");
+            }
+        }
+    }
+}
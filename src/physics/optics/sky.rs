@@ -0,0 +1,288 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::color::observer::Observer;
+use crate::color::xyz::XyzColor;
+use crate::math::vec3::Vec3;
+use crate::physics::optics::illuminant::Daylight;
+use crate::physics::optics::material::emittance::Emittance;
+use crate::physics::optics::material::property::Property;
+use crate::physics::optics::table::Table;
+use crate::physics::units::*;
+use crate::prelude::*;
+use crate::scene::Exposure;
+
+/// Sky as it is written in a scene file.
+///
+/// The scene is expected to have `+Y` pointing up.
+#[derive(Deserialize, JsonSchema)]
+pub struct SkyDefinition {
+    /// Sun elevation above the horizon, in degrees.
+    #[serde(alias = "elevation")]
+    pub sun_elevation: f64,
+
+    /// Sun azimuth in degrees, clockwise from `+Z` towards `+X`, as seen from above.
+    #[serde(default, alias = "azimuth")]
+    pub sun_azimuth: f64,
+
+    /// [Atmospheric turbidity][1]: `2` is a very clear sky, and `10` is a hazy one.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Turbidity#Atmospheric_turbidity
+    #[serde(default = "SkyDefinition::default_turbidity")]
+    pub turbidity: f64,
+}
+
+impl SkyDefinition {
+    pub const fn default_turbidity() -> f64 {
+        3.0
+    }
+}
+
+/// Daylight sky of the [Preetham model][1] with the solar disc.
+///
+/// The sky luminance and chromaticity follow the Perez formula, and the spectrum is
+/// the CIE daylight of that chromaticity. The sun is a black body, which is attenuated
+/// by the Rayleigh and aerosol scattering along its path through the atmosphere.
+/// The sky is dark below the horizon.
+///
+/// [1]: https://courses.cs.duke.edu/cps124/spring08/assign/07_papers/p91-preetham.pdf
+#[derive(Deserialize)]
+#[serde(try_from = "SkyDefinition")]
+pub struct Sky {
+    /// Unit vector towards the sun.
+    pub sun_direction: Vec3,
+
+    /// Perez coefficients `A` to `E` of the luminance, and the chromaticity `x` and `y`.
+    coefficients: [[f64; 5]; 3],
+
+    /// Zenith values, divided by the Perez function at the zenith.
+    scales: [f64; 3],
+
+    /// Daylight components S0, S1, and S2, and their luminances.
+    components: [Table; 3],
+    component_luminances: [f64; 3],
+
+    /// Relative optical air mass along the sun rays.
+    air_mass: f64,
+
+    /// Ångström turbidity coefficient of the aerosols.
+    aerosol_beta: f64,
+}
+
+impl TryFrom<SkyDefinition> for Sky {
+    type Error = anyhow::Error;
+
+    fn try_from(definition: SkyDefinition) -> Result<Self> {
+        if !(0.0..=90.0).contains(&definition.sun_elevation) {
+            bail!("the sun elevation must be within 0° and 90°");
+        }
+        let turbidity = definition.turbidity;
+        if !(1.7..=10.0).contains(&turbidity) {
+            bail!("the turbidity must be within 1.7 and 10");
+        }
+
+        let elevation = definition.sun_elevation.to_radians();
+        let azimuth = definition.sun_azimuth.to_radians();
+        let sun_direction = Vec3::new(
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            elevation.cos() * azimuth.cos(),
+        );
+
+        let t = turbidity;
+        let coefficients = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        // Zenith luminance, in kcd/m², and chromaticity:
+        let theta = FRAC_PI_2 - elevation;
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta);
+        let luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let powers = [theta.powi(3), theta.powi(2), theta, 1.0];
+        let polynomial = |rows: [[f64; 4]; 3]| -> f64 {
+            rows.iter()
+                .zip([t * t, t, 1.0])
+                .map(|(row, factor)| {
+                    factor * row.iter().zip(powers).map(|(a, b)| a * b).sum::<f64>()
+                })
+                .sum()
+        };
+        let x = polynomial([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let y = polynomial([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+        let zenith = [luminance * 1000.0, x, y];
+        let scales = [0, 1, 2].map(|i| zenith[i] / Self::perez(&coefficients[i], 1.0, theta));
+
+        let components = Daylight::components();
+        let component_luminances = [0, 1, 2].map(|i| {
+            let color = XyzColor::from_spectrum(
+                |wavelength| components[i].at(wavelength),
+                Observer::Cie1931,
+            );
+            Exposure::LUMINOUS_EFFICACY * color.luminance()
+        });
+
+        // Kasten's formula, which holds down to the horizon:
+        let air_mass = 1.0 / (elevation.sin() + 0.15 * (93.885 - theta.to_degrees()).powf(-1.253));
+
+        Ok(Self {
+            sun_direction,
+            coefficients,
+            scales,
+            components,
+            component_luminances,
+            air_mass,
+            aerosol_beta: 0.04608 * t - 0.04586,
+        })
+    }
+}
+
+impl Sky {
+    /// Mean angular radius of the solar disc.
+    pub const SUN_ANGULAR_RADIUS: f64 = 0.004_65;
+    /// Effective temperature of the solar photosphere.
+    const SUN_TEMPERATURE: Temperature = Quantity(5772.0);
+
+    /// Spectral radiance of the sky in the direction, without the sun.
+    pub fn sky_radiance(&self, direction: Vec3, wavelength: Length) -> SpectralFluxDensity {
+        let direction = direction.normalize();
+        let cosine_theta = direction.y;
+        if cosine_theta <= 0.0 {
+            return SpectralFluxDensity::ZERO;
+        }
+        let gamma = direction.dot(self.sun_direction).clamp(-1.0, 1.0).acos();
+        let [luminance, x, y] = [0, 1, 2]
+            .map(|i| self.scales[i] * Self::perez(&self.coefficients[i], cosine_theta, gamma));
+
+        let (m1, m2) = Daylight::component_weights(x, y);
+        let [s0, s1, s2] = [0, 1, 2].map(|i| self.components[i].at(wavelength));
+        let [l0, l1, l2] = self.component_luminances;
+        let relative = (s0 + m1 * s1 + m2 * s2).max(0.0);
+        Quantity::from(luminance * relative / (l0 + m1 * l1 + m2 * l2))
+    }
+
+    /// Spectral radiance of the sun, if the direction is within the solar disc, and zero otherwise.
+    pub fn sun_radiance(&self, direction: Vec3, wavelength: Length) -> SpectralFluxDensity {
+        if direction.normalize().dot(self.sun_direction) < Self::SUN_ANGULAR_RADIUS.cos() {
+            return SpectralFluxDensity::ZERO;
+        }
+
+        // Rayleigh and aerosol optical depths, with the wavelength in micrometers:
+        let micros = wavelength.0 * 1e6;
+        let rayleigh = 0.008_735 * micros.powf(-4.08);
+        let aerosol = self.aerosol_beta * micros.powf(-1.3);
+        let transmittance = (-self.air_mass * (rayleigh + aerosol)).exp();
+
+        let emittance = Emittance::BlackBody { temperature: Self::SUN_TEMPERATURE };
+        emittance.at(wavelength) * Bare::from(transmittance)
+    }
+
+    /// Perez formula for the relative sky luminance or chromaticity.
+    fn perez(coefficients: &[f64; 5], cosine_theta: f64, gamma: f64) -> f64 {
+        let [a, b, c, d, e] = *coefficients;
+        (1.0 + a * (b / cosine_theta).exp())
+            * (1.0 + c * (d * gamma).exp() + e * gamma.cos().powi(2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    fn new_sky(sun_elevation: f64, turbidity: f64) -> Sky {
+        Sky::try_from(SkyDefinition {
+            sun_elevation,
+            sun_azimuth: 30.0,
+            turbidity,
+        })
+        .unwrap()
+    }
+
+    /// At the zenith, the spectrum must match the zenith luminance and chromaticity.
+    #[test]
+    fn zenith_ok() {
+        let sky = new_sky(45.0, 3.0);
+        let spectrum = |wavelength| sky.sky_radiance(Vec3::new(0.0, 1.0, 0.0), wavelength);
+        let color = XyzColor::from_spectrum(|wavelength| spectrum(wavelength).0, Observer::Cie1931);
+        assert_abs_diff_eq!(Exposure::LUMINOUS_EFFICACY * color.luminance(), 7320.4, epsilon = 0.1);
+        let (x, y) = color.chromaticity();
+        assert_abs_diff_eq!(x, 0.2457, epsilon = 3e-3);
+        assert_abs_diff_eq!(y, 0.2515, epsilon = 3e-3);
+    }
+
+    /// The sky is brighter around the sun, and it is blue, while the setting sun is reddish.
+    #[test]
+    fn sun_ok() {
+        let sky = new_sky(5.0, 3.0);
+        let away = Vec3::new(-sky.sun_direction.x, 0.5, -sky.sun_direction.z);
+        let wavelength = Length::from_nanos(550.0);
+        assert!(
+            sky.sky_radiance(sky.sun_direction, wavelength) > sky.sky_radiance(away, wavelength)
+        );
+        assert_eq!(sky.sun_radiance(away, wavelength), SpectralFluxDensity::ZERO);
+
+        let (x, _) = XyzColor::from_spectrum(
+            |wavelength| sky.sky_radiance(away, wavelength).0,
+            Observer::Cie1931,
+        )
+        .chromaticity();
+        assert!(x < 0.3, "x: {x}");
+        let (x, _) = XyzColor::from_spectrum(
+            |wavelength| sky.sun_radiance(sky.sun_direction, wavelength).0,
+            Observer::Cie1931,
+        )
+        .chromaticity();
+        assert!(x > 0.4, "x: {x}");
+
+        let noon = new_sky(90.0, 3.0);
+        let (x, _) = XyzColor::from_spectrum(
+            |wavelength| noon.sun_radiance(noon.sun_direction, wavelength).0,
+            Observer::Cie1931,
+        )
+        .chromaticity();
+        assert!((0.32..0.36).contains(&x), "x: {x}");
+    }
+
+    #[test]
+    fn sky_below_horizon_fails() {
+        assert!(
+            Sky::try_from(SkyDefinition {
+                sun_elevation: -1.0,
+                sun_azimuth: 0.0,
+                turbidity: 3.0,
+            })
+            .is_err()
+        );
+    }
+}
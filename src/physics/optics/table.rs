@@ -0,0 +1,283 @@
+use std::fs;
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::physics::units::Length;
+use crate::prelude::*;
+use crate::scene::deserialize_optional_path;
+
+/// Spectral data table, which gets interpolated between the wavelengths.
+#[derive(Clone, Deserialize)]
+#[serde(try_from = "TableDefinition")]
+pub struct Table {
+    /// Strictly increasing wavelengths, in meters.
+    wavelengths: Vec<f64>,
+
+    values: Vec<f64>,
+
+    /// Second derivatives of the natural cubic spline, or empty for the linear interpolation.
+    second_derivatives: Vec<f64>,
+
+    extrapolation: Extrapolation,
+}
+
+/// Table as it is written in a scene file.
+#[derive(Deserialize, JsonSchema)]
+pub struct TableDefinition {
+    /// Inline `[wavelength, value]` pairs.
+    #[serde(default)]
+    pub points: Vec<[f64; 2]>,
+
+    /// Path to a CSV file with the wavelength and value columns, instead of the inline points.
+    ///
+    /// A relative path is resolved against the scene file directory.
+    /// Empty lines, `#`-comments, and a header line are skipped.
+    #[serde(default, deserialize_with = "deserialize_optional_path")]
+    pub path: Option<PathBuf>,
+
+    #[serde(default)]
+    pub wavelength_unit: WavelengthUnit,
+
+    #[serde(default)]
+    pub interpolation: Interpolation,
+
+    #[serde(default)]
+    pub extrapolation: Extrapolation,
+}
+
+#[derive(Copy, Clone, Default, Deserialize, JsonSchema)]
+pub enum WavelengthUnit {
+    #[default]
+    Meter,
+    Micrometer,
+    Nanometer,
+}
+
+#[derive(Copy, Clone, Default, Deserialize, JsonSchema)]
+pub enum Interpolation {
+    /// Piecewise linear.
+    #[default]
+    Linear,
+
+    /// [Natural cubic spline][1], which needs at least three points.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Spline_interpolation
+    Spline,
+}
+
+/// Value outside of the table wavelength range.
+#[derive(Copy, Clone, Default, Deserialize, JsonSchema)]
+pub enum Extrapolation {
+    /// Keep the value of the nearest end of the table.
+    #[default]
+    Clamp,
+
+    /// Zero outside of the table.
+    Zero,
+}
+
+impl TryFrom<TableDefinition> for Table {
+    type Error = anyhow::Error;
+
+    fn try_from(definition: TableDefinition) -> Result<Self> {
+        let points = match &definition.path {
+            Some(path) if definition.points.is_empty() => {
+                let buffer = fs::read_to_string(path)
+                    .with_context(|| format!("failed to read `{path:?}`"))?;
+                Self::parse_csv(&buffer).with_context(|| format!("failed to parse `{path:?}`"))?
+            }
+            Some(_) => bail!("the table must have either the points or the path, but not both"),
+            None => definition.points,
+        };
+        let scale = match definition.wavelength_unit {
+            WavelengthUnit::Meter => 1.0,
+            WavelengthUnit::Micrometer => 1e-6,
+            WavelengthUnit::Nanometer => 1e-9,
+        };
+        let (wavelengths, values): (Vec<f64>, Vec<f64>) = points
+            .into_iter()
+            .map(|[wavelength, value]| (wavelength * scale, value))
+            .unzip();
+        Self::new(wavelengths, values, definition.interpolation, definition.extrapolation)
+    }
+}
+
+impl Table {
+    pub fn new(
+        wavelengths: Vec<f64>,
+        values: Vec<f64>,
+        interpolation: Interpolation,
+        extrapolation: Extrapolation,
+    ) -> Result<Self> {
+        if wavelengths.is_empty() {
+            bail!("the table is empty");
+        }
+        if wavelengths.len() != values.len() {
+            bail!("the table must have as many values as wavelengths");
+        }
+        if wavelengths.windows(2).any(|pair| pair[0] >= pair[1]) {
+            bail!("the table wavelengths must be strictly increasing");
+        }
+        let second_derivatives = match interpolation {
+            Interpolation::Linear => Vec::new(),
+            Interpolation::Spline if wavelengths.len() < 3 => {
+                bail!("the spline interpolation needs at least three points");
+            }
+            Interpolation::Spline => Self::spline(&wavelengths, &values),
+        };
+        Ok(Self {
+            wavelengths,
+            values,
+            second_derivatives,
+            extrapolation,
+        })
+    }
+
+    /// Interpolate the value at the wavelength.
+    pub fn at(&self, wavelength: Length) -> f64 {
+        let wavelength = wavelength.0;
+        let n_points = self.wavelengths.len();
+        if wavelength <= self.wavelengths[0] || wavelength >= self.wavelengths[n_points - 1] {
+            return match self.extrapolation {
+                Extrapolation::Zero
+                    if wavelength < self.wavelengths[0]
+                        || wavelength > self.wavelengths[n_points - 1] =>
+                {
+                    0.0
+                }
+                _ if wavelength <= self.wavelengths[0] => self.values[0],
+                _ => self.values[n_points - 1],
+            };
+        }
+
+        let i = self.wavelengths.partition_point(|x| *x <= wavelength) - 1;
+        let width = self.wavelengths[i + 1] - self.wavelengths[i];
+        let b = (wavelength - self.wavelengths[i]) / width;
+        let a = 1.0 - b;
+        let linear = a * self.values[i] + b * self.values[i + 1];
+        if self.second_derivatives.is_empty() {
+            return linear;
+        }
+        linear
+            + ((a * a * a - a) * self.second_derivatives[i]
+                + (b * b * b - b) * self.second_derivatives[i + 1])
+                * (width * width)
+                / 6.0
+    }
+
+    /// Solve the tridiagonal system for the second derivatives of the natural cubic spline.
+    fn spline(x: &[f64], y: &[f64]) -> Vec<f64> {
+        let n = x.len();
+        let mut second_derivatives = vec![0.0; n];
+        let mut u = vec![0.0; n];
+        for i in 1..(n - 1) {
+            let sigma = (x[i] - x[i - 1]) / (x[i + 1] - x[i - 1]);
+            let p = sigma * second_derivatives[i - 1] + 2.0;
+            second_derivatives[i] = (sigma - 1.0) / p;
+            let slope_difference =
+                (y[i + 1] - y[i]) / (x[i + 1] - x[i]) - (y[i] - y[i - 1]) / (x[i] - x[i - 1]);
+            u[i] = (6.0 * slope_difference / (x[i + 1] - x[i - 1]) - sigma * u[i - 1]) / p;
+        }
+        second_derivatives[n - 1] = 0.0;
+        for i in (0..(n - 1)).rev() {
+            second_derivatives[i] = second_derivatives[i] * second_derivatives[i + 1] + u[i];
+        }
+        second_derivatives
+    }
+
+    fn parse_csv(buffer: &str) -> Result<Vec<[f64; 2]>> {
+        let mut points = Vec::new();
+        let lines = buffer
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        for (i, (line_number, line)) in lines.enumerate() {
+            let mut columns = line.split(',').map(str::trim);
+            let wavelength = columns.next().unwrap_or_default().parse::<f64>();
+            let value = columns.next().unwrap_or_default().parse::<f64>();
+            match (wavelength, value) {
+                (Ok(wavelength), Ok(value)) => points.push([wavelength, value]),
+                _ if i == 0 => {} // header
+                _ => bail!("line {line_number}: expected the wavelength and value"),
+            }
+        }
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    fn new_table(interpolation: Interpolation, extrapolation: Extrapolation) -> Table {
+        Table::new(vec![1.0, 2.0, 4.0], vec![1.0, 4.0, 16.0], interpolation, extrapolation).unwrap()
+    }
+
+    #[test]
+    fn linear_ok() {
+        let table = new_table(Interpolation::Linear, Extrapolation::Clamp);
+        assert_abs_diff_eq!(table.at(Length::from(1.5)), 2.5);
+        assert_abs_diff_eq!(table.at(Length::from(3.0)), 10.0);
+        assert_abs_diff_eq!(table.at(Length::from(4.0)), 16.0);
+        assert_abs_diff_eq!(table.at(Length::from(0.0)), 1.0);
+        assert_abs_diff_eq!(table.at(Length::from(5.0)), 16.0);
+    }
+
+    #[test]
+    fn zero_extrapolation_ok() {
+        let table = new_table(Interpolation::Linear, Extrapolation::Zero);
+        assert_abs_diff_eq!(table.at(Length::from(1.0)), 1.0);
+        assert_abs_diff_eq!(table.at(Length::from(0.5)), 0.0);
+        assert_abs_diff_eq!(table.at(Length::from(4.5)), 0.0);
+    }
+
+    /// The spline must pass through the points, and be exact for a straight line.
+    #[test]
+    fn spline_ok() {
+        let table = new_table(Interpolation::Spline, Extrapolation::Clamp);
+        assert_abs_diff_eq!(table.at(Length::from(2.0)), 4.0, epsilon = 1e-12);
+        let table = Table::new(
+            vec![1.0, 2.0, 4.0, 5.0],
+            vec![3.0, 5.0, 9.0, 11.0],
+            Interpolation::Spline,
+            Extrapolation::Clamp,
+        )
+        .unwrap();
+        assert_abs_diff_eq!(table.at(Length::from(3.3)), 7.6, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn parse_csv_ok() {
+        let points =
+            Table::parse_csv("wavelength,value\n# comment\n\n400, 0.5\n500,0.75\n").unwrap();
+        assert_eq!(points, [[400.0, 0.5], [500.0, 0.75]]);
+        assert!(Table::parse_csv("400,0.5\nfoo,bar\n").is_err());
+    }
+
+    #[test]
+    fn deserialize_ok() {
+        use crate::physics::optics::material::property::Property;
+        use crate::physics::optics::material::transmittance::refraction::AbsoluteRefractiveIndex;
+        use crate::scene::with_base_path;
+
+        let directory = std::env::temp_dir();
+        let path = directory.join(format!("table-{}.csv", std::process::id()));
+        fs::write(&path, "400,1.5\n700,1.4\n").unwrap();
+        let toml = format!(
+            "type = 'Tabulated'\npath = '{}'\nwavelength_unit = 'Nanometer'",
+            path.file_name().unwrap().to_str().unwrap(),
+        );
+        let (index, referenced_paths): (Result<AbsoluteRefractiveIndex>, _) =
+            with_base_path(&directory, || toml::from_str(&toml).map_err(Into::into));
+        fs::remove_file(&path).unwrap();
+
+        let index = index.unwrap();
+        assert_eq!(referenced_paths, [path]);
+        assert_abs_diff_eq!(index.at(Length::from_nanos(550.0)).0, 1.45, epsilon = 1e-12);
+    }
+}
@@ -1,3 +1,3 @@
 pub type Result<T = ()> = anyhow::Result<T>;
-pub use anyhow::Context;
-pub use tracing::{debug, info};
+pub use anyhow::{bail, Context};
+pub use tracing::{debug, info, warn};
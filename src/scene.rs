@@ -1,13 +1,86 @@
-use std::fs;
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fs, mem};
 
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
+use crate::args::BvhOptions;
+use crate::color::observer::Observer;
 use crate::math::vec3::Vec3;
 use crate::physics::optics::material::emittance::Emittance;
+use crate::physics::optics::sky::{Sky, SkyDefinition};
 use crate::prelude::*;
 use crate::surface::Surface;
+use crate::tracer::bvh::Bvh;
+
+thread_local! {
+    /// Scene file, which is being read.
+    static SCENE_FILE: RefCell<Option<SceneFile>> = const { RefCell::new(None) };
+}
+
+struct SceneFile {
+    /// Directory of the scene file.
+    base_path: PathBuf,
+
+    /// Resolved paths of the files, which the scene has referred to so far.
+    referenced_paths: Vec<PathBuf>,
+}
+
+/// Resolve the relative paths against the base path while running the function,
+/// which is meant to deserialize a scene.
+///
+/// Returns the function result along with the resolved paths of all the referenced files.
+pub fn with_base_path<T>(base_path: &Path, f: impl FnOnce() -> T) -> (T, Vec<PathBuf>) {
+    /// Restores the previous scene file, even if the function panics.
+    struct Restore(Option<SceneFile>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCENE_FILE.with(|cell| cell.replace(self.0.take()));
+        }
+    }
+
+    let scene_file = SceneFile {
+        base_path: base_path.to_path_buf(),
+        referenced_paths: Vec::new(),
+    };
+    let _restore = Restore(SCENE_FILE.with(|cell| cell.replace(Some(scene_file))));
+    let result = f();
+    let referenced_paths = SCENE_FILE.with(|cell| {
+        cell.borrow_mut()
+            .as_mut()
+            .map_or_else(Vec::new, |scene_file| mem::take(&mut scene_file.referenced_paths))
+    });
+    (result, referenced_paths)
+}
+
+/// Deserialize a file path, which is resolved against the scene file directory if it is relative.
+pub fn deserialize_path<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<PathBuf, D::Error> {
+    PathBuf::deserialize(deserializer).map(resolve_path)
+}
+
+/// Deserialize an optional file path, see [`deserialize_path`].
+pub fn deserialize_optional_path<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<PathBuf>, D::Error> {
+    Ok(Option::<PathBuf>::deserialize(deserializer)?.map(resolve_path))
+}
+
+fn resolve_path(path: PathBuf) -> PathBuf {
+    SCENE_FILE.with(|cell| match &mut *cell.borrow_mut() {
+        Some(scene_file) => {
+            let path = scene_file.base_path.join(path);
+            scene_file.referenced_paths.push(path.clone());
+            path
+        }
+        None => path,
+    })
+}
 
 /// A scene to render.
 ///
@@ -17,20 +90,101 @@ pub struct Scene {
     #[serde(default)]
     pub camera: Camera,
 
+    /// Standard colorimetric observer, unless it is overridden in the command line.
+    #[serde(default)]
+    pub observer: Option<Observer>,
+
     /// Scene background and ambient color.
     #[serde(default, alias = "ambient_spectrum")]
     pub ambient_emittance: Emittance,
 
+    /// Physically based sky with the sun, which replaces the ambient emittance.
+    #[serde(default)]
+    #[schemars(with = "Option<SkyDefinition>")]
+    pub sky: Option<Sky>,
+
     /// Surfaces to render.
     #[serde(default)]
     pub surfaces: Vec<Surface>,
+
+    /// Named geometries, which are not rendered by themselves, but may be instanced.
+    #[serde(default)]
+    pub geometries: HashMap<String, Vec<Surface>>,
+
+    /// Files, which the scene refers to: the meshes, the tables, and the density grids.
+    #[serde(skip)]
+    pub referenced_paths: Vec<PathBuf>,
 }
 
 impl Scene {
     pub fn read_from(path: &PathBuf) -> Result<Scene> {
         let buffer = fs::read(path).with_context(|| format!("failed to read `{path:?}`"))?;
         let buffer = String::from_utf8(buffer)?;
-        toml::from_str(&buffer).with_context(|| format!("failed to read a scene from `{path:?}`"))
+        let base_path = path.parent().unwrap_or(path);
+        let (scene, referenced_paths) = with_base_path(base_path, || toml::from_str(&buffer));
+        let mut scene: Self =
+            scene.with_context(|| format!("failed to read a scene from `{path:?}`"))?;
+        scene.referenced_paths = referenced_paths;
+        scene.surfaces = Surface::expand_meshes(scene.surfaces)?;
+        for surfaces in scene.geometries.values_mut() {
+            *surfaces = Surface::expand_meshes(mem::take(surfaces))?;
+        }
+        Ok(scene)
+    }
+
+    /// Resolve the instances, and build the bounding volume hierarchy of the scene surfaces.
+    pub fn build_bvh(&mut self, options: &BvhOptions) -> Result<Bvh<Surface>> {
+        let mut library = GeometryLibrary {
+            definitions: mem::take(&mut self.geometries),
+            built: HashMap::new(),
+            options,
+        };
+        let surfaces = library.resolve_instances(mem::take(&mut self.surfaces))?;
+        info!(n_geometries = library.built.len(), "resolved the instances");
+        Ok(Bvh::new(surfaces, options))
+    }
+}
+
+/// Builds the geometry hierarchies on demand, so that each one is built only once
+/// and shared between its instances.
+struct GeometryLibrary<'a> {
+    definitions: HashMap<String, Vec<Surface>>,
+    built: HashMap<String, Arc<Bvh<Surface>>>,
+    options: &'a BvhOptions,
+}
+
+impl<'a> GeometryLibrary<'a> {
+    fn resolve_instances(&mut self, mut surfaces: Vec<Surface>) -> Result<Vec<Surface>> {
+        for surface in &mut surfaces {
+            match surface {
+                Surface::Instance(instance) => {
+                    let bvh = self.get(&instance.geometry)?;
+                    instance.resolve(bvh);
+                }
+                Surface::BoundedFog(fog) => {
+                    let boundary = self.resolve_instances(mem::take(&mut fog.boundary))?;
+                    fog.resolve(Bvh::new(boundary, self.options));
+                }
+                _ => {}
+            }
+        }
+        Ok(surfaces)
+    }
+
+    fn get(&mut self, name: &str) -> Result<Arc<Bvh<Surface>>> {
+        if let Some(bvh) = self.built.get(name) {
+            return Ok(bvh.clone());
+        }
+        // The definition gets removed while it's being resolved, which also catches cycles:
+        let Some(surfaces) = self.definitions.remove(name) else {
+            bail!("geometry `{name}` is not defined, or it instances itself");
+        };
+        let surfaces = self
+            .resolve_instances(surfaces)
+            .with_context(|| format!("failed to resolve geometry `{name}`"))?;
+        let bvh = Arc::new(Bvh::new(surfaces, self.options));
+        self.built.insert(name.to_string(), bvh.clone());
+        Ok(bvh)
     }
 }
 
@@ -44,13 +198,41 @@ pub struct Camera {
     #[serde(default)]
     pub look_at: Vec3,
 
-    /// Vertical field-of-view angle, in degrees.
+    /// Vertical field-of-view angle of the perspective projection, in degrees.
     #[serde(default = "Camera::default_vertical_fov", alias = "vfov")]
     pub vertical_fov: f64,
 
+    /// Mapping of the image onto the camera rays.
+    #[serde(default)]
+    pub projection: Projection,
+
     /// Up **direction** (not a point).
     #[serde(default = "Camera::default_up")]
     pub up: Vec3,
+
+    /// Lens aperture, the camera is a pinhole one when it is not set.
+    ///
+    /// The aperture is ignored by the fisheye and equirectangular projections.
+    #[serde(default)]
+    pub aperture: Option<Aperture>,
+
+    /// Distance from the camera to the plane in focus, defaults to the distance to the «look at» point.
+    #[serde(default)]
+    pub focus_distance: Option<f64>,
+
+    /// Physical exposure, which turns the luminance into the sensor values of the output image.
+    ///
+    /// When it is set, the tone mapping skips its own luminance scaling, so that images
+    /// of different scenes and light levels are comparable.
+    #[serde(default)]
+    pub exposure: Option<Exposure>,
+
+    /// Sensor height, in meters, which together with the field of view defines the lens focal length.
+    ///
+    /// It is only used to convert the f-number into the aperture size.
+    /// The default is the height of the 35 mm full-frame sensor.
+    #[serde(default = "Camera::default_sensor_height")]
+    pub sensor_height: f64,
 }
 
 impl Camera {
@@ -65,6 +247,58 @@ impl Camera {
     pub const fn default_up() -> Vec3 {
         Vec3::new(0.0, 1.0, 0.0)
     }
+
+    pub const fn default_sensor_height() -> f64 {
+        0.024
+    }
+
+    /// Lens focal length, in meters.
+    pub fn focal_length(&self) -> f64 {
+        self.sensor_height / 2.0 / (self.vertical_fov / 2.0).to_radians().tan()
+    }
+
+    /// F-number of the aperture, if any.
+    pub fn f_number(&self) -> Option<f64> {
+        match &self.aperture {
+            None => None,
+            Some(Aperture {
+                size: ApertureSize::Radius { radius },
+                ..
+            }) => Some(self.focal_length() / radius / 2.0),
+            Some(Aperture {
+                size: ApertureSize::FNumber { f_number },
+                ..
+            }) => Some(*f_number),
+        }
+    }
+
+    /// Multiplier, which converts the integrated Y tristimulus value into the sensor value,
+    /// or [`None`] if the exposure is not set.
+    pub fn exposure_scale(&self) -> Result<Option<f64>> {
+        let Some(exposure) = &self.exposure else {
+            return Ok(None);
+        };
+        let Some(f_number) = exposure.f_number.or_else(|| self.f_number()) else {
+            bail!("the exposure needs either its own f-number, or the camera aperture");
+        };
+        info!(f_number, ev100 = exposure.ev100(f_number), exposure.compensation);
+        Ok(Some(exposure.scale(f_number)))
+    }
+
+    /// Aperture radius, in meters, or zero for a pinhole camera.
+    pub fn aperture_radius(&self) -> f64 {
+        match &self.aperture {
+            None => 0.0,
+            Some(Aperture {
+                size: ApertureSize::Radius { radius },
+                ..
+            }) => *radius,
+            Some(Aperture {
+                size: ApertureSize::FNumber { f_number },
+                ..
+            }) => self.focal_length() / f_number / 2.0,
+        }
+    }
 }
 
 impl Default for Camera {
@@ -73,7 +307,191 @@ impl Default for Camera {
             location: Self::default_location(),
             look_at: Vec3::default(),
             vertical_fov: Self::default_vertical_fov(),
+            projection: Projection::default(),
             up: Self::default_up(),
+            aperture: None,
+            focus_distance: None,
+            exposure: None,
+            sensor_height: Self::default_sensor_height(),
+        }
+    }
+}
+
+/// Physical camera exposure settings.
+#[derive(Deserialize, JsonSchema)]
+pub struct Exposure {
+    /// [ISO film speed][1].
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Film_speed#ISO
+    #[serde(default = "Exposure::default_iso")]
+    pub iso: f64,
+
+    /// Shutter time, in seconds.
+    #[serde(alias = "shutter")]
+    pub shutter_time: f64,
+
+    /// F-number, defaults to the camera aperture.
+    #[serde(default)]
+    pub f_number: Option<f64>,
+
+    /// Exposure compensation, in stops.
+    #[serde(default)]
+    pub compensation: f64,
+}
+
+impl Exposure {
+    /// Maximum luminous efficacy, in lumens per watt.
+    pub const LUMINOUS_EFFICACY: f64 = 683.0;
+    /// [Saturation-based][1] calibration constant, «78 / q», with the lens factor `q` of 0.65.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Film_speed#Saturation-based_speed
+    const SATURATION_CONSTANT: f64 = 78.0 / 0.65;
+
+    pub const fn default_iso() -> f64 {
+        100.0
+    }
+
+    /// Multiplier, which converts the integrated Y tristimulus value into the sensor value,
+    /// so that the saturation luminance maps to one.
+    pub fn scale(&self, f_number: f64) -> f64 {
+        Self::LUMINOUS_EFFICACY * self.shutter_time * self.iso * self.compensation.exp2()
+            / (Self::SATURATION_CONSTANT * f_number * f_number)
+    }
+
+    /// [Exposure value][1] at ISO 100.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Exposure_value
+    pub fn ev100(&self, f_number: f64) -> f64 {
+        (f_number * f_number / self.shutter_time * 100.0 / self.iso).log2()
+    }
+}
+
+#[derive(Copy, Clone, Default, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Projection {
+    /// Rays start at the camera location and go through the viewport, which spans the `vertical_fov`.
+    #[default]
+    Perspective,
+
+    /// Rays are parallel to the principal axis, and start on the viewport of the given height.
+    Orthographic {
+        /// Viewport height, in meters.
+        height: f64,
+    },
+
+    /// [Equidistant fisheye][1]: the angle from the principal axis is proportional
+    /// to the distance from the image center.
+    ///
+    /// The points outside of the field of view stay black.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Fisheye_lens#Mapping_function
+    Fisheye {
+        /// Field-of-view angle along the image height, in degrees.
+        #[serde(default = "Projection::default_fisheye_fov")]
+        fov: f64,
+    },
+
+    /// [Equirectangular panorama][1]: the full 360° longitude along the image width,
+    /// and the full 180° latitude along the image height.
+    ///
+    /// The image is expected to be twice as wide as it is high.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Equirectangular_projection
+    Equirectangular,
+}
+
+impl Projection {
+    pub const fn default_fisheye_fov() -> f64 {
+        180.0
+    }
+}
+
+/// [Thin lens][1] aperture, which makes the objects away from the focal plane blurry.
+///
+/// [1]: https://www.pbr-book.org/3ed-2018/Camera_Models/Projective_Camera_Models#TheThinLensModelandDepthofField
+#[derive(Deserialize, JsonSchema)]
+pub struct Aperture {
+    #[serde(flatten)]
+    pub size: ApertureSize,
+
+    /// Aperture shape, which also defines the bokeh shape.
+    #[serde(default)]
+    pub shape: ApertureShape,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ApertureSize {
+    /// Aperture radius, in meters.
+    Radius { radius: f64 },
+
+    /// [F-number][1], the ratio of the focal length to the aperture diameter.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/F-number
+    FNumber { f_number: f64 },
+}
+
+#[derive(Default, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum ApertureShape {
+    #[default]
+    Circle,
+
+    /// Regular polygon, formed by the diaphragm blades.
+    Polygon {
+        n_blades: u32,
+
+        /// Rotation of the polygon, in degrees.
+        #[serde(default)]
+        rotation: f64,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    /// The saturation luminance must be «1.2 × 2^EV100» candelas per square meter.
+    #[test]
+    fn exposure_scale_ok() {
+        let camera: Camera = toml::from_str(
+            "aperture = { f_number = 16 }\nexposure = { iso = 100, shutter_time = 0.01 }",
+        )
+        .unwrap();
+        let scale = camera.exposure_scale().unwrap().unwrap();
+        let ev100 = camera.exposure.unwrap().ev100(16.0);
+        assert_abs_diff_eq!(ev100, 14.64, epsilon = 0.01);
+        let saturation_luminance = Exposure::LUMINOUS_EFFICACY / scale;
+        assert_abs_diff_eq!(saturation_luminance, 1.2 * ev100.exp2(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn exposure_without_f_number_fails() {
+        let camera: Camera = toml::from_str("exposure = { shutter_time = 0.01 }").unwrap();
+        assert!(camera.exposure_scale().is_err());
+    }
+
+    /// The relative paths are only resolved within the scene, even if its reading panics.
+    #[test]
+    fn base_path_ok() {
+        #[derive(Deserialize)]
+        struct File {
+            #[serde(deserialize_with = "deserialize_path")]
+            path: PathBuf,
         }
+
+        let path = |toml: &str| toml::from_str::<File>(toml).unwrap().path;
+        let (resolved_path, referenced_paths) =
+            with_base_path(Path::new("scenes"), || path("path = 'a.obj'"));
+        assert_eq!(resolved_path, Path::new("scenes/a.obj"));
+        assert_eq!(referenced_paths, [Path::new("scenes/a.obj")]);
+        let (resolved_path, _) = with_base_path(Path::new("scenes"), || path("path = '/a.obj'"));
+        assert_eq!(resolved_path, Path::new("/a.obj"));
+
+        let result = std::panic::catch_unwind(|| with_base_path(Path::new("scenes"), || panic!()));
+        assert!(result.is_err());
+        assert_eq!(path("path = 'a.obj'"), Path::new("a.obj"));
     }
 }
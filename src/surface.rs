@@ -1,6 +1,8 @@
 mod fog;
-mod sphere;
-mod triangle;
+mod instance;
+mod mesh;
+pub mod sphere;
+pub mod triangle;
 
 use std::ops::Range;
 
@@ -11,7 +13,10 @@ use crate::math::aabb::{Aabb, Bounded};
 use crate::math::hit::*;
 use crate::math::ray::Ray;
 use crate::math::sequence::Sequence;
-use crate::surface::fog::UniformFog;
+use crate::prelude::*;
+use crate::surface::fog::{BoundedFog, HeterogeneousFog, UniformFog};
+use crate::surface::instance::Instance;
+use crate::surface::mesh::Mesh;
 use crate::surface::sphere::Sphere;
 use crate::surface::triangle::Triangle;
 
@@ -22,6 +27,37 @@ pub enum Surface {
     Sphere(Sphere),
     Triangle(Triangle),
     UniformFog(UniformFog),
+    HeterogeneousFog(HeterogeneousFog),
+    BoundedFog(BoundedFog),
+
+    /// Triangle mesh, which gets expanded into separate triangles when the scene is being read.
+    Mesh(Mesh),
+
+    /// Transformed instance of a named geometry.
+    Instance(Instance),
+}
+
+impl Surface {
+    /// Expand the meshes into separate triangles, including those of the fog boundaries,
+    /// and leave the other surfaces as they are.
+    pub fn expand_meshes(surfaces: Vec<Self>) -> Result<Vec<Self>> {
+        let mut expanded = Vec::with_capacity(surfaces.len());
+        for surface in surfaces {
+            match surface {
+                Self::Mesh(mesh) => {
+                    let triangles = mesh.load()?;
+                    info!(n_triangles = triangles.len(), "loaded the mesh");
+                    expanded.extend(triangles.into_iter().map(Self::Triangle));
+                }
+                Self::BoundedFog(mut fog) => {
+                    fog.boundary = Self::expand_meshes(fog.boundary)?;
+                    expanded.push(Self::BoundedFog(fog));
+                }
+                surface => expanded.push(surface),
+            }
+        }
+        Ok(expanded)
+    }
 }
 
 impl Bounded for Surface {
@@ -30,6 +66,10 @@ impl Bounded for Surface {
             Self::Sphere(sphere) => sphere.aabb(),
             Self::Triangle(triangle) => triangle.aabb(),
             Self::UniformFog(fog) => fog.aabb,
+            Self::HeterogeneousFog(fog) => fog.aabb,
+            Self::BoundedFog(fog) => fog.aabb(),
+            Self::Mesh(_) => unreachable!("meshes must be expanded before rendering"),
+            Self::Instance(instance) => instance.aabb(),
         }
     }
 }
@@ -40,6 +80,10 @@ impl<S: Sequence<f64>> Hittable<S> for Surface {
             Self::Sphere(sphere) => sphere.hit(by_ray, distance, rng),
             Self::Triangle(triangle) => triangle.hit(by_ray, distance, rng),
             Self::UniformFog(fog) => fog.hit(by_ray, distance, rng),
+            Self::HeterogeneousFog(fog) => fog.hit(by_ray, distance, rng),
+            Self::BoundedFog(fog) => fog.hit(by_ray, distance, rng),
+            Self::Mesh(_) => unreachable!("meshes must be expanded before rendering"),
+            Self::Instance(instance) => instance.hit(by_ray, distance, rng),
         }
     }
 }
@@ -1,13 +1,21 @@
+use std::fs;
 use std::ops::Range;
+use std::path::PathBuf;
 
 use schemars::JsonSchema;
 use serde::Deserialize;
 
 use crate::math::aabb::{Aabb, Bounded};
 use crate::math::hit::*;
+use crate::math::noise::fractal_noise;
 use crate::math::ray::Ray;
 use crate::math::sequence::Sequence;
+use crate::math::vec3::Vec3;
 use crate::physics::optics::material::Material;
+use crate::prelude::*;
+use crate::scene::deserialize_path;
+use crate::surface::Surface;
+use crate::tracer::bvh::Bvh;
 
 #[derive(Deserialize, JsonSchema)]
 pub struct UniformFog {
@@ -36,15 +44,16 @@ impl Bounded for UniformFog {
 
 impl<S: Sequence<f64>> Hittable<S> for UniformFog {
     fn hit(&self, by_ray: &Ray, distance_range: &Range<f64>, rng: &mut S) -> Option<Hit> {
-        let Some((min_distance, max_distance)) = self.aabb.hit(by_ray, distance_range) else {
-            return None;
-        };
+        let (min_distance, max_distance) = self.aabb.hit(by_ray, distance_range)?;
         assert!(min_distance.is_finite());
-        let hit_distance = min_distance - 1.0 / self.density * rng.next().ln();
+        let density = self.density * self.material.majorant_scale();
+        let hit_distance = min_distance + sample_free_flight(density, rng);
         if hit_distance < max_distance {
+            let normal = -by_ray.direction.normalize();
             let hit = Hit {
                 location: by_ray.at(hit_distance),
-                normal: -by_ray.direction.normalize(),
+                normal,
+                face_normal: normal,
                 distance: hit_distance,
                 type_: HitType::Enter, // FIXME: what should go here?
                 material: &self.material,
@@ -55,3 +64,436 @@ impl<S: Sequence<f64>> Hittable<S> for UniformFog {
         }
     }
 }
+
+/// Uniform fog inside a closed boundary, such as a sphere, a closed mesh, or a transformed box.
+///
+/// Put it inside a transmittive surface of the same shape to get a scattering interior,
+/// for example, a coloured liquid in a glass.
+#[derive(Deserialize, JsonSchema)]
+pub struct BoundedFog {
+    /// Closed surfaces, whose normals point outwards. Their materials are ignored.
+    ///
+    /// A non-convex boundary is fine, the fog fills all the parts inside.
+    pub boundary: Vec<Surface>,
+
+    /// Fog density.
+    #[serde(default = "UniformFog::default_density")]
+    pub density: f64,
+
+    pub material: Material,
+
+    #[serde(skip)]
+    bvh: Option<Bvh<Surface>>,
+}
+
+impl BoundedFog {
+    /// Step over the boundary, so that the same boundary hit is not found again.
+    const BOUNDARY_GAP: f64 = 1e-9;
+
+    /// Set the boundary hierarchy, which is built from the resolved boundary surfaces.
+    pub fn resolve(&mut self, bvh: Bvh<Surface>) {
+        self.bvh = Some(bvh);
+    }
+
+    #[inline]
+    const fn bvh(&self) -> &Bvh<Surface> {
+        match &self.bvh {
+            Some(bvh) => bvh,
+            None => panic!("bounded fogs must be resolved before rendering"),
+        }
+    }
+
+    /// Find the nearest segment of the ray inside the boundary, starting from the distance.
+    fn segment<S: Sequence<f64>>(
+        &self,
+        by_ray: &Ray,
+        min_distance: f64,
+        rng: &mut S,
+    ) -> Option<(f64, f64)> {
+        let boundary_hit = self
+            .bvh()
+            .hit(by_ray, &(min_distance..f64::INFINITY), rng)?;
+        match boundary_hit.type_ {
+            // The ray is already inside:
+            HitType::Leave => Some((min_distance, boundary_hit.distance)),
+
+            HitType::Enter => {
+                let entry_distance = boundary_hit.distance;
+                let exit_range = (entry_distance + Self::BOUNDARY_GAP)..f64::INFINITY;
+                let exit_hit = self.bvh().hit(by_ray, &exit_range, rng)?;
+                Some((entry_distance, exit_hit.distance))
+            }
+        }
+    }
+}
+
+impl Bounded for BoundedFog {
+    #[inline]
+    fn aabb(&self) -> Aabb {
+        self.bvh().aabb()
+    }
+}
+
+impl<S: Sequence<f64>> Hittable<S> for BoundedFog {
+    fn hit(&self, by_ray: &Ray, distance_range: &Range<f64>, rng: &mut S) -> Option<Hit> {
+        let density = self.density * self.material.majorant_scale();
+        let mut min_distance = distance_range.start;
+
+        // Sample the segments one by one, since a non-convex boundary may have many of them:
+        loop {
+            let (entry_distance, exit_distance) = self.segment(by_ray, min_distance, rng)?;
+            if entry_distance >= distance_range.end {
+                break None;
+            }
+            let hit_distance = entry_distance + sample_free_flight(density, rng);
+            if hit_distance < exit_distance.min(distance_range.end) {
+                let normal = -by_ray.direction.normalize();
+                break Some(Hit {
+                    location: by_ray.at(hit_distance),
+                    normal,
+                    face_normal: normal,
+                    distance: hit_distance,
+                    type_: HitType::Enter,
+                    material: &self.material,
+                });
+            }
+            min_distance = exit_distance + Self::BOUNDARY_GAP;
+        }
+    }
+}
+
+/// Fog, whose density varies inside the boundary box, such as smoke, clouds, or ground fog.
+///
+/// The scattering distance is sampled with the [delta tracking][1] against the maximal density.
+///
+/// [1]: https://en.wikipedia.org/wiki/Woodcock_tracking
+#[derive(Deserialize, JsonSchema)]
+pub struct HeterogeneousFog {
+    /// Axis-aligned boundary box, which the density field gets stretched to.
+    pub aabb: Aabb,
+
+    /// Maximal fog density, which the density field gets multiplied by.
+    #[serde(default = "UniformFog::default_density")]
+    pub density: f64,
+
+    pub field: DensityField,
+
+    pub material: Material,
+}
+
+/// Relative density in `[0, 1]`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum DensityField {
+    /// Density grid, which gets interpolated between the voxel centers.
+    Grid(#[schemars(with = "GridDefinition")] Grid),
+
+    /// Fractal value noise.
+    Noise(#[schemars(with = "NoiseDefinition")] Noise),
+}
+
+/// Fractal value noise as it is written in a scene file.
+#[derive(Deserialize, JsonSchema)]
+pub struct NoiseDefinition {
+    /// Typical size of the largest features, in meters.
+    pub scale: f64,
+
+    #[serde(default = "NoiseDefinition::default_n_octaves")]
+    pub n_octaves: u32,
+
+    /// Noise level in `[0, 1)`, below which the density is zero.
+    /// Higher thresholds give sparser clouds.
+    #[serde(default)]
+    pub threshold: f64,
+}
+
+impl NoiseDefinition {
+    pub const fn default_n_octaves() -> u32 {
+        4
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "NoiseDefinition")]
+pub struct Noise {
+    scale: f64,
+    n_octaves: u32,
+    threshold: f64,
+}
+
+impl TryFrom<NoiseDefinition> for Noise {
+    type Error = anyhow::Error;
+
+    fn try_from(definition: NoiseDefinition) -> Result<Self> {
+        if !(0.0..1.0).contains(&definition.threshold) {
+            bail!("the noise threshold must be within 0 (inclusive) and 1 (exclusive)");
+        }
+        Ok(Self {
+            scale: definition.scale,
+            n_octaves: definition.n_octaves,
+            threshold: definition.threshold,
+        })
+    }
+}
+
+impl Noise {
+    fn at(&self, point: Vec3) -> f64 {
+        let noise = fractal_noise(point / self.scale, self.n_octaves);
+        ((noise - self.threshold) / (1.0 - self.threshold)).clamp(0.0, 1.0)
+    }
+}
+
+/// Density grid as it is written in a scene file.
+#[derive(Deserialize, JsonSchema)]
+pub struct GridDefinition {
+    /// Path to a text file, which contains the grid size along X, Y, and Z,
+    /// followed by the voxel values with X changing the fastest and Z the slowest.
+    ///
+    /// The numbers are separated by whitespace, and `#`-comments are skipped.
+    /// A relative path is resolved against the scene file directory.
+    #[serde(deserialize_with = "deserialize_path")]
+    pub path: PathBuf,
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "GridDefinition")]
+pub struct Grid {
+    size: [usize; 3],
+    values: Vec<f64>,
+}
+
+impl TryFrom<GridDefinition> for Grid {
+    type Error = anyhow::Error;
+
+    fn try_from(definition: GridDefinition) -> Result<Self> {
+        let path = definition.path;
+        let buffer =
+            fs::read_to_string(&path).with_context(|| format!("failed to read `{path:?}`"))?;
+        Self::parse(&buffer).with_context(|| format!("failed to parse `{path:?}`"))
+    }
+}
+
+impl Grid {
+    fn parse(buffer: &str) -> Result<Self> {
+        let mut numbers = buffer
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(str::split_whitespace);
+        let mut size = [0; 3];
+        for dimension in &mut size {
+            *dimension = numbers.next().context("missing the grid size")?.parse()?;
+        }
+        if size.contains(&0) {
+            bail!("the grid must not be empty");
+        }
+        let values = numbers
+            .map(|number| number.parse::<f64>().map_err(Into::into))
+            .collect::<Result<Vec<f64>>>()?;
+        let n_values: usize = size.iter().product();
+        if values.len() != n_values {
+            bail!("expected {n_values} values, got {}", values.len());
+        }
+        if values.iter().any(|value| !(0.0..=1.0).contains(value)) {
+            bail!("the grid values must be within 0 and 1");
+        }
+        Ok(Self { size, values })
+    }
+
+    /// Trilinearly interpolate at the point, whose coordinates are normalized to `[0, 1]`.
+    fn at(&self, point: Vec3) -> f64 {
+        let mut indices = [[0; 2]; 3];
+        let mut weights = [0.0; 3];
+        for (axis, coordinate) in [point.x, point.y, point.z].into_iter().enumerate() {
+            let size = self.size[axis];
+            let coordinate = (coordinate * size as f64 - 0.5).clamp(0.0, (size - 1) as f64);
+            let index = (coordinate as usize).min(size - 1);
+            indices[axis] = [index, (index + 1).min(size - 1)];
+            weights[axis] = coordinate - index as f64;
+        }
+        let mut value = 0.0;
+        for corner in 0..8 {
+            let (i, j, k) = (corner & 1, (corner >> 1) & 1, corner >> 2);
+            let weight = [i, j, k]
+                .into_iter()
+                .enumerate()
+                .map(|(axis, bit)| {
+                    if bit == 1 {
+                        weights[axis]
+                    } else {
+                        1.0 - weights[axis]
+                    }
+                })
+                .product::<f64>();
+            let index =
+                indices[0][i] + self.size[0] * (indices[1][j] + self.size[1] * indices[2][k]);
+            value += weight * self.values[index];
+        }
+        value
+    }
+}
+
+impl DensityField {
+    /// Get the relative density at the point, which is also given normalized to the boundary box.
+    fn at(&self, point: Vec3, normalized_point: Vec3) -> f64 {
+        match self {
+            Self::Grid(grid) => grid.at(normalized_point),
+            Self::Noise(noise) => noise.at(point),
+        }
+    }
+}
+
+impl HeterogeneousFog {
+    /// Get the absolute density at the point.
+    fn density_at(&self, point: Vec3) -> f64 {
+        let normalized_point = (point - self.aabb.min_point) / self.aabb.size();
+        self.density * self.field.at(point, normalized_point)
+    }
+}
+
+impl Bounded for HeterogeneousFog {
+    #[inline]
+    fn aabb(&self) -> Aabb {
+        self.aabb
+    }
+}
+
+impl<S: Sequence<f64>> Hittable<S> for HeterogeneousFog {
+    fn hit(&self, by_ray: &Ray, distance_range: &Range<f64>, rng: &mut S) -> Option<Hit> {
+        let (min_distance, max_distance) = self.aabb.hit(by_ray, distance_range)?;
+        assert!(min_distance.is_finite());
+
+        // Sample the tentative collisions with the majorant density,
+        // and accept them with the probability of the real-to-majorant ratio:
+        let majorant_scale = self.material.majorant_scale();
+        let mut hit_distance = min_distance;
+        loop {
+            hit_distance += sample_free_flight(self.density * majorant_scale, rng);
+            if hit_distance >= max_distance {
+                break None;
+            }
+            let location = by_ray.at(hit_distance);
+            if rng.next() * self.density < self.density_at(location) {
+                let normal = -by_ray.direction.normalize();
+                break Some(Hit {
+                    location,
+                    normal,
+                    face_normal: normal,
+                    distance: hit_distance,
+                    type_: HitType::Enter,
+                    material: &self.material,
+                });
+            }
+        }
+    }
+}
+
+/// Sample the [free-flight distance][1] through a medium of the given density.
+///
+/// [1]: https://www.pbr-book.org/3ed-2018/Light_Transport_II_Volume_Rendering/Sampling_Volume_Scattering#HomogeneousMedium
+fn sample_free_flight<S: Sequence<f64>>(density: f64, rng: &mut S) -> f64 {
+    // The sequence is in `[0, 1)`, so flip it to never take the logarithm of zero:
+    -(1.0 - rng.next()).ln() / density
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::args::BvhOptions;
+    use crate::math::sequence::RandomSequence;
+    use crate::surface::sphere::Sphere;
+    use crate::tracer::bvh::Builder;
+
+    #[test]
+    fn noise_threshold_fails() {
+        let field = |threshold| {
+            toml::from_str::<DensityField>(&format!(
+                "type = 'Noise'\nscale = 1\nthreshold = {threshold}"
+            ))
+        };
+        assert!(field(0.5).is_ok());
+        assert!(field(1.0).is_err());
+        assert!(field(1.5).is_err());
+        assert!(field(-0.1).is_err());
+    }
+
+    #[test]
+    fn parse_grid_ok() {
+        let grid = Grid::parse("# size\n2 1 2\n0 1\n0.5 0.25 # z = 1\n").unwrap();
+        assert_abs_diff_eq!(grid.at(Vec3::new(0.25, 0.5, 0.25)), 0.0);
+        assert_abs_diff_eq!(grid.at(Vec3::new(0.5, 0.5, 0.25)), 0.5);
+        assert_abs_diff_eq!(grid.at(Vec3::new(0.5, 0.5, 0.5)), 0.4375);
+        assert!(Grid::parse("2 2 2\n0 1").is_err());
+        assert!(Grid::parse("1 1 1\n2").is_err());
+    }
+
+    /// With the constant density, the transmittance must follow the Beer–Lambert law.
+    #[test]
+    fn delta_tracking_ok() {
+        let fog = HeterogeneousFog {
+            aabb: Aabb {
+                min_point: Vec3::new(-1.0, -1.0, -1.0),
+                max_point: Vec3::new(1.0, 1.0, 1.0),
+            },
+            density: 2.0,
+            field: DensityField::Grid(Grid { size: [1, 1, 1], values: vec![0.25] }),
+            material: Material::default(),
+        };
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut rng = RandomSequence::with_seed(42);
+        let n_rays = 100_000;
+        let n_passed = (0..n_rays)
+            .filter(|_| fog.hit(&ray, &(0.0..f64::INFINITY), &mut rng).is_none())
+            .count();
+        assert_abs_diff_eq!(n_passed as f64 / n_rays as f64, (-1.0_f64).exp(), epsilon = 5e-3);
+    }
+
+    /// The ray passes two unit spheres, and the fog must only be inside them.
+    #[test]
+    fn bounded_fog_ok() {
+        let new_sphere = |z| {
+            Surface::Sphere(Sphere {
+                center: Vec3::new(0.0, 0.0, z),
+                radius: 1.0,
+                material: Material::default(),
+            })
+        };
+        let mut fog = BoundedFog {
+            boundary: Vec::new(),
+            density: 0.25,
+            material: Material::default(),
+            bvh: None,
+        };
+        fog.resolve(Bvh::new(
+            vec![new_sphere(0.0), new_sphere(3.0)],
+            &BvhOptions {
+                max_leaf_size: 1,
+                builder: Builder::Median,
+            },
+        ));
+
+        let mut rng = RandomSequence::with_seed(42);
+        let mut pass_fraction = |origin_z: f64| {
+            let ray = Ray::new(Vec3::new(0.0, 0.0, origin_z), Vec3::new(0.0, 0.0, 1.0));
+            let n_rays = 100_000;
+            let mut n_passed = 0;
+            for _ in 0..n_rays {
+                match fog.hit(&ray, &(0.0..f64::INFINITY), &mut rng) {
+                    Some(hit) => {
+                        let z = hit.location.z;
+                        assert!((-1.0..1.0).contains(&z) || (2.0..4.0).contains(&z), "z: {z}");
+                    }
+                    None => n_passed += 1,
+                }
+            }
+            n_passed as f64 / n_rays as f64
+        };
+
+        // Outside, the ray goes through 4 meters of the fog:
+        assert_abs_diff_eq!(pass_fraction(-5.0), (-1.0_f64).exp(), epsilon = 5e-3);
+
+        // Inside the first sphere, it is 3 meters:
+        assert_abs_diff_eq!(pass_fraction(0.0), (-0.75_f64).exp(), epsilon = 5e-3);
+    }
+}
@@ -0,0 +1,116 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::math::aabb::{Aabb, Bounded};
+use crate::math::hit::*;
+use crate::math::ray::Ray;
+use crate::math::sequence::Sequence;
+use crate::math::transform::{Transform, Transformation};
+use crate::surface::Surface;
+use crate::tracer::bvh::Bvh;
+
+/// Instance of a named geometry, placed into the scene with its own transformation.
+#[derive(Deserialize, JsonSchema)]
+pub struct Instance {
+    /// Geometry name in the scene `geometries`.
+    pub geometry: String,
+
+    /// Transformations of the geometry, applied in the given order.
+    #[serde(default)]
+    #[schemars(with = "Vec<Transformation>")]
+    transform: Transform,
+
+    #[serde(skip)]
+    bvh: Option<Arc<Bvh<Surface>>>,
+}
+
+impl Instance {
+    /// Set the geometry hierarchy, which is shared between the instances of the same geometry.
+    pub fn resolve(&mut self, bvh: Arc<Bvh<Surface>>) {
+        self.bvh = Some(bvh);
+    }
+
+    #[inline]
+    pub const fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    #[inline]
+    pub fn bvh(&self) -> &Bvh<Surface> {
+        self.bvh
+            .as_deref()
+            .expect("instances must be resolved before rendering")
+    }
+}
+
+impl Bounded for Instance {
+    fn aabb(&self) -> Aabb {
+        self.transform.transform_aabb(&self.bvh().aabb())
+    }
+}
+
+impl<S: Sequence<f64>> Hittable<S> for Instance {
+    /// Transform the ray into the geometry space, and the hit back into the world space.
+    fn hit(&self, by_ray: &Ray, distance_range: &Range<f64>, rng: &mut S) -> Option<Hit> {
+        let direction = self.transform.inverse_transform_vector(by_ray.direction);
+
+        // Distances get scaled along with the ray direction:
+        let scale = direction.length();
+        let local_ray = Ray::new(self.transform.inverse_transform_point(by_ray.origin), direction);
+        let local_range = (distance_range.start * scale)..(distance_range.end * scale);
+
+        let hit = self.bvh().hit(&local_ray, &local_range, rng)?;
+        let distance = hit.distance / scale;
+        Some(Hit {
+            location: by_ray.at(distance),
+            normal: self.transform.transform_normal(hit.normal).normalize(),
+            face_normal: self.transform.transform_normal(hit.face_normal).normalize(),
+            distance,
+            ..hit
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::args::BvhOptions;
+    use crate::math::sequence::RandomSequence;
+    use crate::math::transform::Transform;
+    use crate::math::vec3::Vec3;
+    use crate::tracer::bvh::Builder;
+
+    #[test]
+    fn hit_ok() {
+        let sphere: Surface =
+            toml::from_str("type = 'Sphere'\ncenter = [0, 0, 0]\nradius = 1\nmaterial = {}")
+                .unwrap();
+        let mut instance = Instance {
+            geometry: String::new(),
+            transform: Transform::scaling(Vec3::splat(2.0))
+                .then(Transform::translation(Vec3::new(0.0, 0.0, 5.0))),
+            bvh: None,
+        };
+        instance.resolve(Arc::new(Bvh::new(
+            vec![sphere],
+            &BvhOptions {
+                max_leaf_size: 1,
+                builder: Builder::Median,
+            },
+        )));
+
+        let ray = Ray::new(Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let hit = instance
+            .hit(&ray, &(0.0..f64::INFINITY), &mut RandomSequence::new())
+            .unwrap();
+        assert_abs_diff_eq!(hit.distance, 5.0 - 3.0_f64.sqrt(), epsilon = 1e-12);
+        assert!(hit.type_ == HitType::Enter);
+        let expected_normal = Vec3::new(1.0, 0.0, -(3.0_f64.sqrt())).normalize();
+        assert!(hit.normal.abs_diff_eq(expected_normal, 1e-12), "actual: {}", hit.normal);
+    }
+}
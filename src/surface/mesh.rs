@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::SplitWhitespace;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::math::vec3::Vec3;
+use crate::physics::optics::material::Material;
+use crate::prelude::*;
+use crate::scene::deserialize_path;
+use crate::surface::triangle::Triangle;
+
+/// [Wavefront OBJ][1] mesh.
+///
+/// It gets expanded into separate triangles when the scene is being read.
+///
+/// [1]: https://en.wikipedia.org/wiki/Wavefront_.obj_file
+#[derive(Deserialize, JsonSchema)]
+pub struct Mesh {
+    /// Path to the `.obj` file.
+    ///
+    /// A relative path is resolved against the scene file directory.
+    #[serde(deserialize_with = "deserialize_path")]
+    pub path: PathBuf,
+
+    /// Material of the faces that are not in a `usemtl` group.
+    #[serde(default)]
+    pub material: Material,
+
+    /// Materials of the `usemtl` groups, by the group material name.
+    #[serde(default)]
+    pub materials: HashMap<String, Material>,
+}
+
+impl Mesh {
+    /// Read the mesh file and triangulate it.
+    pub fn load(self) -> Result<Vec<Triangle>> {
+        let path = self.path.clone();
+        let buffer =
+            fs::read_to_string(&path).with_context(|| format!("failed to read `{path:?}`"))?;
+        self.parse(&buffer)
+            .with_context(|| format!("failed to parse `{path:?}`"))
+    }
+
+    fn parse(self, buffer: &str) -> Result<Vec<Triangle>> {
+        let default_material = Arc::new(self.material);
+        let materials: HashMap<String, Arc<Material>> = self
+            .materials
+            .into_iter()
+            .map(|(name, material)| (name, Arc::new(material)))
+            .collect();
+
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut triangles = Vec::new();
+        let mut material = &default_material;
+
+        for (line_number, line) in buffer.lines().enumerate() {
+            let line_number = line_number + 1;
+            let line = line.split_once('#').map_or(line, |(line, _)| line);
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("v") => {
+                    vertices.push(
+                        Self::parse_vec3(&mut tokens)
+                            .with_context(|| format!("invalid vertex at line {line_number}"))?,
+                    );
+                }
+                Some("vn") => {
+                    normals.push(
+                        Self::parse_vec3(&mut tokens).with_context(|| {
+                            format!("invalid vertex normal at line {line_number}")
+                        })?,
+                    );
+                }
+                Some("f") => {
+                    let face = tokens
+                        .map(|token| Self::parse_face_vertex(token, vertices.len(), normals.len()))
+                        .collect::<Result<Vec<_>>>()
+                        .with_context(|| format!("invalid face at line {line_number}"))?;
+                    if face.len() < 3 {
+                        bail!("face with less than 3 vertices at line {line_number}");
+                    }
+                    // Triangulate the polygon as a fan, assuming it is convex:
+                    for i in 1..(face.len() - 1) {
+                        let face = [face[0], face[i], face[i + 1]];
+                        let triangle_vertices = face.map(|(vertex, _)| vertices[vertex]);
+                        let triangle_normals = face.map(|(_, normal)| normal.map(|i| normals[i]));
+                        triangles.push(Self::new_triangle(
+                            triangle_vertices,
+                            triangle_normals,
+                            material.clone(),
+                        ));
+                    }
+                }
+                Some("usemtl") => {
+                    let name = tokens.next().unwrap_or_default();
+                    material = materials.get(name).with_context(|| {
+                        format!(
+                            "material `{name}` at line {line_number} is not defined in the scene"
+                        )
+                    })?;
+                }
+                // Texture coordinates, groups, smoothing, and the others are not supported:
+                _ => {}
+            }
+        }
+
+        Ok(triangles)
+    }
+
+    /// Create the triangle, and if the vertex normals are present,
+    /// orient the triangle normal the same way as the vertex normals.
+    ///
+    /// The vertex normals are used for smooth shading, if all three are present.
+    fn new_triangle(
+        vertices: [Vec3; 3],
+        normals: [Option<Vec3>; 3],
+        material: Arc<Material>,
+    ) -> Triangle {
+        let face_normal = (vertices[1] - vertices[0]).cross(vertices[2] - vertices[0]);
+        let invert_normal = normals
+            .into_iter()
+            .flatten()
+            .fold(Vec3::ZERO, |sum, normal| sum + normal)
+            .dot(face_normal)
+            < 0.0;
+        let triangle = Triangle::new(vertices, material, invert_normal);
+        match normals {
+            [Some(normal_1), Some(normal_2), Some(normal_3)] => triangle.with_normals([
+                normal_1.normalize(),
+                normal_2.normalize(),
+                normal_3.normalize(),
+            ]),
+            _ => triangle,
+        }
+    }
+
+    fn parse_vec3(tokens: &mut SplitWhitespace) -> Result<Vec3> {
+        let mut next =
+            || -> Result<f64> { Ok(tokens.next().context("expected 3 coordinates")?.parse()?) };
+        Ok(Vec3::new(next()?, next()?, next()?))
+    }
+
+    /// Parse a face vertex in any of the `v`, `v/vt`, `v//vn`, or `v/vt/vn` forms,
+    /// and return the zero-based vertex and normal indices.
+    fn parse_face_vertex(
+        token: &str,
+        n_vertices: usize,
+        n_normals: usize,
+    ) -> Result<(usize, Option<usize>)> {
+        let mut indices = token.split('/');
+        let vertex = Self::parse_index(indices.next().unwrap_or_default(), n_vertices)?;
+        let normal = match indices.nth(1) {
+            Some(index) if !index.is_empty() => Some(Self::parse_index(index, n_normals)?),
+            _ => None,
+        };
+        Ok((vertex, normal))
+    }
+
+    /// Parse the one-based index, which is relative to the end when negative.
+    fn parse_index(index: &str, n_elements: usize) -> Result<usize> {
+        let index: isize = index
+            .parse()
+            .with_context(|| format!("invalid index `{index}`"))?;
+        let resolved = if index < 0 {
+            n_elements.checked_add_signed(index)
+        } else {
+            (index as usize).checked_sub(1)
+        };
+        resolved
+            .filter(|index| *index < n_elements)
+            .with_context(|| format!("index `{index}` is out of bounds"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_mesh(materials: &[&str]) -> Mesh {
+        Mesh {
+            path: PathBuf::new(),
+            material: Material::default(),
+            materials: materials
+                .iter()
+                .map(|name| (name.to_string(), Material::default()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parse_ok() {
+        let buffer = "
+            # Unit square and a triangle below it.
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            v 0 -1 0
+            vn 0 0 -1
+            usemtl square
+            f 1//1 2//1 3//1 4//1
+            usemtl triangle
+            f -5 -1 -4
+        ";
+        let triangles = new_mesh(&["square", "triangle"]).parse(buffer).unwrap();
+        assert_eq!(triangles.len(), 3);
+    }
+
+    #[test]
+    fn undefined_material_fails() {
+        assert!(new_mesh(&[]).parse("usemtl unknown").is_err());
+    }
+
+    #[test]
+    fn index_out_of_bounds_fails() {
+        assert!(new_mesh(&[]).parse("v 0 0 0\nv 1 0 0\nf 1 2 3").is_err());
+    }
+}
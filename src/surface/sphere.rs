@@ -11,9 +11,11 @@ use crate::physics::optics::material::Material;
 
 #[derive(Deserialize, JsonSchema)]
 pub struct Sphere {
-    center: Vec3,
-    radius: f64,
-    material: Material,
+    pub center: Vec3,
+    pub radius: f64,
+
+    #[serde(default)]
+    pub material: Material,
 }
 
 impl Bounded for Sphere {
@@ -60,6 +62,7 @@ impl<S> Hittable<S> for Sphere {
             location,
             type_,
             normal,
+            face_normal: normal,
             material: &self.material,
         })
     }
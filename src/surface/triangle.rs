@@ -1,4 +1,5 @@
 use std::ops::Range;
+use std::sync::Arc;
 
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -12,12 +13,69 @@ use crate::physics::optics::material::Material;
 #[derive(Deserialize, JsonSchema)]
 pub struct Triangle {
     vertices: [Vec3; 3],
-    material: Material,
+    material: Arc<Material>,
+
+    /// Optional per-vertex normals, which get interpolated for smooth shading.
+    ///
+    /// The interpolated normal is always oriented to the same side as the face normal.
+    #[serde(default)]
+    normals: Option<[Vec3; 3]>,
 
     #[serde(default)]
     invert_normal: bool,
 }
 
+impl Triangle {
+    pub const fn new(vertices: [Vec3; 3], material: Arc<Material>, invert_normal: bool) -> Self {
+        Self {
+            vertices,
+            material,
+            normals: None,
+            invert_normal,
+        }
+    }
+
+    #[inline]
+    pub const fn vertices(&self) -> &[Vec3; 3] {
+        &self.vertices
+    }
+
+    #[inline]
+    pub fn material(&self) -> &Material {
+        &self.material
+    }
+
+    /// Unit normal of the front face, which is the one that gets entered.
+    #[inline]
+    pub fn face_normal(&self) -> Vec3 {
+        let normal = (self.vertices[1] - self.vertices[0])
+            .cross(self.vertices[2] - self.vertices[0])
+            .normalize();
+        if self.invert_normal { -normal } else { normal }
+    }
+
+    /// Set the per-vertex normals for smooth shading.
+    pub const fn with_normals(mut self, normals: [Vec3; 3]) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    /// Interpolate the vertex normals at the barycentric coordinates,
+    /// or return the face normal if the vertex normals are not set.
+    #[inline]
+    fn shading_normal(&self, face_normal: Vec3, u: f64, v: f64) -> Vec3 {
+        let Some(normals) = &self.normals else {
+            return face_normal;
+        };
+        let normal = ((1.0 - u - v) * normals[0] + u * normals[1] + v * normals[2]).normalize();
+        if normal.dot(face_normal) < 0.0 {
+            -normal
+        } else {
+            normal
+        }
+    }
+}
+
 impl Bounded for Triangle {
     fn aabb(&self) -> Aabb {
         Aabb {
@@ -56,19 +114,27 @@ impl<S> Hittable<S> for Triangle {
 
         let distance = f * edge_2.dot(q);
         if distance_range.contains(&distance) {
-            let mut normal = edge_1.cross(edge_2).normalize();
-            if self.invert_normal {
-                normal = -normal;
-            }
-            let (normal, hit_type) = if normal.dot(by_ray.direction) < 0.0 {
-                (normal, HitType::Enter)
+            let face_normal = self.face_normal();
+
+            // The hit type is defined by the geometric normal:
+            let (face_normal, hit_type) = if face_normal.dot(by_ray.direction) < 0.0 {
+                (face_normal, HitType::Enter)
+            } else {
+                (-face_normal, HitType::Leave)
+            };
+
+            // The shading normal must still face the ray, otherwise fall back to the face normal:
+            let normal = self.shading_normal(face_normal, u, v);
+            let normal = if normal.dot(by_ray.direction) < 0.0 {
+                normal
             } else {
-                (-normal, HitType::Leave)
+                face_normal
             };
 
             Some(Hit {
                 location: by_ray.at(distance),
                 normal,
+                face_normal,
                 distance,
                 type_: hit_type,
                 material: &self.material,
@@ -78,3 +144,40 @@ impl<S> Hittable<S> for Triangle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::math::sequence::RandomSequence;
+
+    #[test]
+    fn interpolated_normal_ok() {
+        let normals = [
+            Vec3::new(-1.0, 0.0, -1.0).normalize(),
+            Vec3::new(1.0, 0.0, -1.0).normalize(),
+            Vec3::new(0.0, 1.0, -1.0).normalize(),
+        ];
+        let triangle = Triangle::new(
+            [
+                Vec3::new(-1.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ],
+            Arc::default(),
+            false,
+        )
+        .with_normals(normals);
+
+        // The vertex normals point away from the face normal, so that they get inverted:
+        let ray = Ray::with_two_points(Vec3::new(0.0, 0.5, 1.0), Vec3::new(0.0, 0.5, 0.0));
+        let hit = triangle
+            .hit(&ray, &(0.0..f64::INFINITY), &mut RandomSequence::new())
+            .unwrap();
+        assert!(hit.type_ == HitType::Enter);
+        let expected = -(normals[0] + normals[1] + 2.0 * normals[2]).normalize();
+        assert!(hit.normal.abs_diff_eq(expected, 1e-9), "actual: {}", hit.normal);
+        assert_abs_diff_eq!(hit.normal.length(), 1.0);
+    }
+}
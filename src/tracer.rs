@@ -1,7 +1,10 @@
 pub mod bvh;
+pub mod frame;
+mod light;
 pub mod progress;
 mod viewport;
 
+use std::f64::consts::PI;
 use std::sync::{Arc, Mutex};
 
 use fastrand::Rng;
@@ -9,6 +12,7 @@ use rayon::prelude::*;
 use tracing::info;
 
 use crate::args::TracerOptions;
+use crate::color::observer::Observer;
 use crate::color::xyz::XyzColor;
 use crate::math::hit::*;
 use crate::math::ray::Ray;
@@ -18,17 +22,23 @@ use crate::math::vec3::Vec3;
 use crate::physics::optics::material::emittance::Emittance;
 use crate::physics::optics::material::property::Property;
 use crate::physics::optics::material::transmittance::refraction::RelativeRefractiveIndex;
+use crate::physics::optics::sky::Sky;
 use crate::physics::units::*;
 use crate::prelude::*;
 use crate::scene::Camera;
 use crate::surface::Surface;
 use crate::tracer::bvh::Bvh;
+use crate::tracer::frame::{Frame, Pixel};
+use crate::tracer::light::{power_heuristic, Lights};
 use crate::tracer::progress::new_progress;
 use crate::tracer::viewport::Viewport;
 
-pub struct Tracer<'a> {
-    bvh: Bvh<'a, Surface>,
+pub struct Tracer {
+    bvh: Bvh<Surface>,
+    lights: Lights,
     ambient_emittance: Emittance,
+    sky: Option<Sky>,
+    observer: Observer,
     camera: Camera,
     options: TracerOptions,
     output_width: u32,
@@ -36,24 +46,31 @@ pub struct Tracer<'a> {
     viewport: Viewport,
 }
 
-impl<'a> Tracer<'a> {
+impl Tracer {
     const MAX_WAVELENGTH: Length = Quantity::from_nanos(830.0);
     const MIN_WAVELENGTH: Length = Quantity::from_nanos(360.0);
     const SPECTRUM_WIDTH: Length = Quantity(Self::MAX_WAVELENGTH.0 - Self::MIN_WAVELENGTH.0);
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        bvh: Bvh<'a, Surface>,
+        bvh: Bvh<Surface>,
         ambient_emittance: Emittance,
+        sky: Option<Sky>,
+        observer: Observer,
         camera: Camera,
         options: TracerOptions,
         output_width: u32,
         output_height: u32,
     ) -> Self {
         let viewport = Viewport::new(&camera, output_width, output_height);
+        let lights = Lights::new(bvh.surfaces(), sky.as_ref());
 
         Self {
             bvh,
+            lights,
             ambient_emittance,
+            sky,
+            observer,
             camera,
             options,
             output_width,
@@ -62,66 +79,109 @@ impl<'a> Tracer<'a> {
         }
     }
 
-    pub fn trace(&self) -> Result<Vec<(u32, Vec<XyzColor>)>> {
-        info!(self.options.n_samples_per_pixel);
+    /// Render the passes of samples into the frame, until each pixel gets the requested number
+    /// of samples. The pixel samples are the XYZ integrals of the spectral radiance.
+    ///
+    /// The callback is called after each pass, for example, to write a checkpoint.
+    ///
+    /// Each sample is seeded by its pixel and index, so the result does not depend
+    /// on how the samples are split into the passes and runs.
+    pub fn trace(&self, frame: &mut Frame, mut on_pass: impl FnMut(&Frame) -> Result) -> Result {
+        info!(self.options.n_samples_per_pixel, self.options.n_samples_per_pass);
         info!(self.options.n_max_bounces, self.options.min_hit_distance);
+        info!(self.options.roulette_depth, self.options.roulette_survival_probability);
         info!(%self.camera.location);
         info!(%self.camera.look_at);
         info!(%self.camera.up);
         info!(self.camera.vertical_fov);
+        info!(aperture_radius = self.camera.aperture_radius(), self.camera.focus_distance);
         info!(%self.viewport.dx);
         info!(%self.viewport.dy);
-
-        let mut y_indices: Vec<u32> = (0..self.output_height).collect();
-        fastrand::shuffle(&mut y_indices);
-
-        let mut rows = Vec::with_capacity(self.output_width as usize);
-        let progress =
-            Arc::new(Mutex::new(new_progress(self.output_height as u64, "tracing rows")?));
-
-        y_indices
-            .into_par_iter()
-            .map(|y| {
-                let mut rng = Rng::new();
-                let row: Vec<XyzColor> = (0..self.output_width)
-                    .map(|x| self.render_pixel(x, y, &mut rng))
-                    .collect();
+        assert_eq!((frame.width(), frame.height()), (self.output_width, self.output_height));
+
+        let n_samples_left = self
+            .options
+            .n_samples_per_pixel
+            .saturating_sub(frame.min_n_samples());
+        let n_passes = n_samples_left.div_ceil(self.options.n_samples_per_pass);
+        info!(n_samples_left, n_passes);
+        let progress = Arc::new(Mutex::new(new_progress(
+            u64::from(n_passes) * u64::from(self.output_height),
+            "tracing rows",
+        )?));
+
+        while frame.min_n_samples() < self.options.n_samples_per_pixel {
+            frame.par_rows_mut().for_each(|(y, row)| {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let n_samples = self.options.n_samples_per_pass.min(
+                        self.options
+                            .n_samples_per_pixel
+                            .saturating_sub(pixel.n_samples),
+                    );
+                    self.render_pixel(x as u32, y, pixel, n_samples);
+                }
                 progress.lock().unwrap().inc(1);
-                (y, row)
-            })
-            .collect_into_vec(&mut rows);
+            });
+            on_pass(frame)?;
+        }
 
         progress.lock().unwrap().finish();
-        Ok(rows)
+        Ok(())
     }
 
+    /// Add the samples to the pixel.
     #[inline]
-    fn render_pixel(&self, x: u32, y: u32, rng: &mut Rng) -> XyzColor {
-        let mut subpixel_sequence = Halton2::new(5, 3).offset(Vec2::new(rng.f64(), rng.f64()));
-        let mut wavelength_sequence = VanDerCorput::new(2);
-        let mut diffusion_sequence = RandomSequence::new();
-        let mut effect_check_sequence = RandomSequence::new();
-
-        (0..self.options.n_samples_per_pixel)
-            .map(|_| {
-                let ray = {
-                    let subpixel = subpixel_sequence.next();
-                    let viewport_point =
-                        self.camera.look_at + self.viewport.cast_ray(x, y, subpixel);
-                    Ray::with_two_points(self.camera.location, viewport_point)
-                };
-                let wavelength = Self::MIN_WAVELENGTH
-                    + Self::SPECTRUM_WIDTH * Bare::from(wavelength_sequence.next());
-                let density = self.trace_ray(
-                    ray,
-                    wavelength,
-                    self.options.n_max_bounces,
-                    &mut effect_check_sequence,
-                    &mut diffusion_sequence,
-                );
-                XyzColor::from_wavelength(wavelength) * density.0
-            })
-            .sum::<XyzColor>()
+    fn render_pixel(&self, x: u32, y: u32, pixel: &mut Pixel, n_samples: u32) {
+        let first_index = u64::from(pixel.n_samples);
+        let subpixel_offset = {
+            let mut rng = Rng::with_seed(Self::seed(x, y, u64::MAX));
+            Vec2::new(rng.f64(), rng.f64())
+        };
+        let mut subpixel_sequence = Halton2::new(5, 3).offset(subpixel_offset).skip(first_index);
+        let mut wavelength_sequence = VanDerCorput::new(2).skip(first_index);
+        let mut lens_sequence = Halton2::new(7, 11)
+            .offset(subpixel_offset)
+            .skip(first_index);
+
+        for index in first_index..(first_index + u64::from(n_samples)) {
+            let mut rng = Rng::with_seed(Self::seed(x, y, index));
+            let mut diffusion_sequence = RandomSequence::with_seed(rng.u64(..));
+            let mut effect_check_sequence = RandomSequence::with_seed(rng.u64(..));
+
+            let wavelength = Self::MIN_WAVELENGTH
+                + Self::SPECTRUM_WIDTH * Bare::from(wavelength_sequence.next());
+            let ray = self
+                .viewport
+                .cast_ray(x, y, subpixel_sequence.next(), lens_sequence.next());
+            let Some(ray) = ray else {
+                // The point is outside of the projection, and stays black:
+                pixel.n_samples += 1;
+                continue;
+            };
+            let density = self.trace_ray(
+                ray,
+                wavelength,
+                self.options.n_max_bounces,
+                &mut effect_check_sequence,
+                &mut diffusion_sequence,
+            );
+
+            // Monte-Carlo estimate of the integrals over the spectrum:
+            pixel.sum += XyzColor::from_wavelength(wavelength, self.observer)
+                * (density.0 * Self::SPECTRUM_WIDTH.0);
+            pixel.n_samples += 1;
+        }
+    }
+
+    /// Seed of the pixel sample, [SplitMix64][1] of its coordinates and index.
+    ///
+    /// [1]: https://prng.di.unimi.it/splitmix64.c
+    #[inline]
+    const fn seed(x: u32, y: u32, index: u64) -> u64 {
+        let mut z = ((y as u64) << 32 | x as u64) ^ index.wrapping_mul(0x9E3779B97F4A7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
     }
 
     /// Trace the ray and return the resulting color.
@@ -134,38 +194,79 @@ impl<'a> Tracer<'a> {
         effect_check_sequence: &mut impl Sequence<f64>,
         diffusion_sequence: &mut impl Sequence<Vec2>,
     ) -> SpectralFluxDensity {
-        let distance_range = self.options.min_hit_distance..f64::INFINITY;
         let scene_emittance = self.ambient_emittance.at(wavelength);
 
         let mut total_flux_density = SpectralFluxDensity::ZERO;
         let mut total_attenuation = Bare::from(1.0);
 
-        for _ in 0..n_bounces_left {
-            if total_attenuation < Bare::from(self.options.min_attenuation) {
+        // Density of the last diffuse scattering, which is needed to weight the emitter hits:
+        let mut diffusion_density = None;
+
+        for i_bounce in 0..n_bounces_left {
+            if total_attenuation <= Bare::from(self.options.min_attenuation) {
                 break;
             }
-            let hit = self.bvh.hit(&ray, &distance_range, effect_check_sequence);
+            if i_bounce >= self.options.roulette_depth {
+                // Russian roulette: terminate the path or boost the survivor to compensate:
+                if effect_check_sequence.next() >= self.options.roulette_survival_probability {
+                    break;
+                }
+                total_attenuation *= Bare::from(1.0 / self.options.roulette_survival_probability);
+            }
+            let hit = self.hit(&ray, wavelength, effect_check_sequence);
             let Some(hit) = hit else {
                 // The ray didn't hit anything, finish the tracing:
-                total_flux_density += total_attenuation * scene_emittance;
+                total_flux_density += total_attenuation
+                    * self
+                        .miss_emittance(&ray, wavelength, diffusion_density)
+                        .unwrap_or(scene_emittance);
                 break;
             };
 
             if hit.type_ == HitType::Enter && let Some(emittance) = &hit.material.emittance {
-                total_flux_density += total_attenuation * emittance.at(wavelength);
+                // The emitter might have also been sampled directly from the previous hit:
+                let weight = diffusion_density.map_or(1.0, |density| {
+                    power_heuristic(density, self.lights.density(ray.origin, &hit))
+                });
+                total_flux_density += total_attenuation * emittance.at(wavelength) * weight;
             }
 
-            let (scattered_ray, attenuation) = if let Some((ray, attenuation)) =
-                Self::trace_refraction(&ray, wavelength, &hit, effect_check_sequence)
-            {
+            let (scattered_ray, attenuation) = if let Some(scattering) = &hit.material.scattering {
+                diffusion_density = None;
+                let (direction, weight) =
+                    scattering.sample(ray.direction, diffusion_sequence.next());
+                let attenuation = scattering.albedo.at(wavelength) * Bare::from(weight);
+                (Ray::new(hit.location, direction), attenuation)
+            } else if let Some((ray, attenuation)) = Self::trace_refraction(
+                &ray,
+                wavelength,
+                &hit,
+                effect_check_sequence,
+                diffusion_sequence,
+            ) {
+                diffusion_density = None;
                 (ray, attenuation)
             } else if let Some((ray, attenuation)) =
                 Self::trace_diffusion(&hit, wavelength, effect_check_sequence, diffusion_sequence)
             {
+                // Only sample the lights if the scattered ray is going to be traced as well,
+                // otherwise the weights would not add up:
+                if i_bounce + 1 != n_bounces_left {
+                    total_flux_density += total_attenuation
+                        * self.sample_lights(
+                            &hit,
+                            attenuation,
+                            wavelength,
+                            effect_check_sequence,
+                            diffusion_sequence,
+                        );
+                }
+                diffusion_density = Some(hit.normal.dot(ray.direction) / PI);
                 (ray, attenuation)
             } else if let Some((ray, attenuation)) =
                 Self::trace_specular_reflection(&ray, wavelength, &hit, diffusion_sequence)
             {
+                diffusion_density = None;
                 (ray, attenuation)
             } else {
                 // There's no scattered ray (for example, the surface is not reflective nor refractive).
@@ -180,6 +281,100 @@ impl<'a> Tracer<'a> {
         total_flux_density
     }
 
+    /// Look up the sky in the direction of the ray, which has not hit anything.
+    ///
+    /// Returns `None` when the scene has no sky.
+    fn miss_emittance(
+        &self,
+        ray: &Ray,
+        wavelength: Length,
+        diffusion_density: Option<f64>,
+    ) -> Option<SpectralFluxDensity> {
+        let sky = self.sky.as_ref()?;
+
+        // The sun might have also been sampled directly from the previous hit:
+        let weight = diffusion_density
+            .map_or(1.0, |density| power_heuristic(density, self.lights.sun_density()));
+        Some(
+            sky.sky_radiance(ray.direction, wavelength)
+                + sky.sun_radiance(ray.direction, wavelength) * weight,
+        )
+    }
+
+    /// Find the nearest hit, skipping the null collisions with the media,
+    /// whose density depends on the wavelength.
+    fn hit(
+        &self,
+        ray: &Ray,
+        wavelength: Length,
+        effect_check_sequence: &mut impl Sequence<f64>,
+    ) -> Option<Hit> {
+        let mut distance_range = self.options.min_hit_distance..f64::INFINITY;
+        loop {
+            let hit = self.bvh.hit(ray, &distance_range, effect_check_sequence)?;
+            match &hit.material.scattering {
+                Some(scattering)
+                    if effect_check_sequence.next() >= scattering.density_ratio(wavelength) =>
+                {
+                    // Continue the tracking from the rejected collision:
+                    distance_range.start = hit.distance;
+                }
+                _ => break Some(hit),
+            }
+        }
+    }
+
+    /// Sample a random light from the diffuse hit, and return the light's contribution
+    /// weighted against the diffuse scattering by the power heuristic.
+    fn sample_lights(
+        &self,
+        hit: &Hit,
+        albedo: Bare,
+        wavelength: Length,
+        effect_check_sequence: &mut impl Sequence<f64>,
+        diffusion_sequence: &mut impl Sequence<Vec2>,
+    ) -> SpectralFluxDensity {
+        let Some(sample) = self.lights.sample(
+            hit.location,
+            effect_check_sequence.next(),
+            diffusion_sequence.next(),
+        ) else {
+            return SpectralFluxDensity::ZERO;
+        };
+        let cosine = hit.normal.dot(sample.direction);
+        if cosine <= 0.0 {
+            // The light is below the surface.
+            return SpectralFluxDensity::ZERO;
+        }
+
+        let shadow_ray = Ray::new(hit.location, sample.direction);
+        let emittance = match self.hit(&shadow_ray, wavelength, effect_check_sequence) {
+            // Only the sun is visible, when nothing is hit:
+            None => match &self.sky {
+                Some(sky) if self.lights.is_sun(&sample) => {
+                    sky.sun_radiance(sample.direction, wavelength)
+                }
+                _ => return SpectralFluxDensity::ZERO,
+            },
+            Some(light_hit) => {
+                if light_hit.type_ != HitType::Enter || !self.lights.is_sampled(&sample, &light_hit)
+                {
+                    // The light is occluded.
+                    return SpectralFluxDensity::ZERO;
+                }
+                let Some(emittance) = &light_hit.material.emittance else {
+                    return SpectralFluxDensity::ZERO;
+                };
+                emittance.at(wavelength)
+            }
+        };
+
+        // Lambertian BRDF is «albedo / π»:
+        let diffusion_density = cosine / PI;
+        let weight = power_heuristic(sample.density, diffusion_density);
+        albedo * emittance * (diffusion_density / sample.density * weight)
+    }
+
     /// Trace [Lambertian reflectance][1].
     ///
     /// [1]: https://en.wikipedia.org/wiki/Lambertian_reflectance
@@ -202,14 +397,18 @@ impl<'a> Tracer<'a> {
             return None;
         }
 
+        // The cosine-weighted direction cancels out the cosine term of the Lambertian BRDF,
+        // so that the attenuation is just the albedo:
         let ray = Ray::new(hit.location, hit.normal + Vec3::sample_unit_vector(diffusion_sequence));
-        // The «length / 2» accounts for its reflected intensity in the ray's direction (the max length is 1 + 1).
-        let attenuation = reflectance.attenuation.at(wavelength) * ray.direction.length() / 2.0;
+        let attenuation = reflectance.attenuation.at(wavelength);
         Some((ray, attenuation))
     }
 
     /// Trace a possible refraction using [Snell's law][1] in [vector form][2].
     ///
+    /// For a rough surface, the law is applied to a sampled microfacet normal,
+    /// and the microfacet reflection is traced here as well.
+    ///
     /// [1]: https://en.wikipedia.org/wiki/Snell%27s_law#Vector_form
     /// [2]: https://physics.stackexchange.com/a/436252/11966
     fn trace_refraction(
@@ -217,6 +416,7 @@ impl<'a> Tracer<'a> {
         wavelength: Length,
         hit: &Hit,
         effect_check_sequence: &mut impl Sequence<f64>,
+        diffusion_sequence: &mut impl Sequence<Vec2>,
     ) -> Option<(Ray, Bare)> {
         // Checking whether the body is dielectric:
         let Some(transmittance) = &hit.material.transmittance else {
@@ -234,37 +434,64 @@ impl<'a> Tracer<'a> {
             },
         };
 
-        let cosine_theta_1 = (-hit.normal.dot(incident_ray.direction)).min(1.0);
-        assert!(cosine_theta_1 >= 0.0);
+        let attenuation = if hit.type_ == HitType::Leave {
+            // Hit from inside, apply the exponential decay coefficient:
+            (Length::from(-hit.distance) * transmittance.attenuation_coefficient.at(wavelength))
+                .exp()
+        } else {
+            Bare::ONE
+        };
 
-        let sin_theta_2 = refractive_index.relative().0 * (1.0 - cosine_theta_1.powi(2)).sqrt();
-        if sin_theta_2 > 1.0 {
-            // Total internal reflection, refraction is not possible.
-            return None;
-        }
+        let normal = match &transmittance.microfacets {
+            Some(microfacets) => microfacets.sample_normal(
+                hit.normal,
+                -incident_ray.direction,
+                diffusion_sequence.next(),
+            ),
+            None => hit.normal,
+        };
 
-        if refractive_index.reflectance(cosine_theta_1) > Bare::from(effect_check_sequence.next()) {
-            // Reflectance wins.
-            return None;
-        }
+        let cosine_theta_1 = (-normal.dot(incident_ray.direction)).clamp(0.0, 1.0);
+        let sin_theta_2 = refractive_index.relative().0 * (1.0 - cosine_theta_1.powi(2)).sqrt();
+        let reflectance = match &hit.material.coating {
+            Some(coating) => coating.reflectance(
+                hit.location,
+                wavelength,
+                refractive_index.incident.0,
+                (refractive_index.refracted.0, 0.0),
+                cosine_theta_1,
+            ),
+            None => refractive_index.reflectance(cosine_theta_1),
+        };
+        let is_reflected = sin_theta_2 > 1.0 // total internal reflection
+            || reflectance > Bare::from(effect_check_sequence.next());
 
-        // Snell's law:
-        let direction = {
+        let direction = if is_reflected {
+            let Some(_) = transmittance.microfacets else {
+                // Leave the smooth reflection to the reflectance.
+                return None;
+            };
+            incident_ray.direction.reflect_about(normal)
+        } else {
+            // Snell's law:
             let cosine_theta_2 = (1.0 - sin_theta_2.powi(2)).sqrt();
             let mu = refractive_index.relative().0;
-            mu * incident_ray.direction + hit.normal * (mu * cosine_theta_1 - cosine_theta_2)
+            mu * incident_ray.direction + normal * (mu * cosine_theta_1 - cosine_theta_2)
         };
         let ray = Ray::new(hit.location, direction);
 
-        let attenuation = if hit.type_ == HitType::Leave {
-            // Hit from inside, apply the exponential decay coefficient:
-            (Length::from(-hit.distance) * transmittance.attenuation_coefficient.at(wavelength))
-                .exp()
-        } else {
-            Bare::ONE
+        let Some(microfacets) = &transmittance.microfacets else {
+            return Some((ray, attenuation));
         };
-
-        Some((ray, attenuation))
+        let cosine_incoming = direction.dot(hit.normal);
+        if (cosine_incoming > 0.0) != is_reflected {
+            // The microfacet has scattered the ray to the wrong side of the surface,
+            // which means the path gets absorbed:
+            return Some((ray, Bare::ZERO));
+        }
+        let cosine_outgoing = -incident_ray.direction.dot(hit.normal);
+        let weight = microfacets.weight(cosine_outgoing, cosine_incoming);
+        Some((ray, attenuation * Bare::from(weight)))
     }
 
     /// Trace [specular reflection][1].
@@ -279,12 +506,44 @@ impl<'a> Tracer<'a> {
         let Some(reflectance) = &hit.material.reflectance else {
             return None;
         };
-        let mut ray = Ray::new(hit.location, incident_ray.direction.reflect_about(hit.normal));
+        let mut attenuation = reflectance.attenuation.at(wavelength);
+
+        let normal = match &reflectance.microfacets {
+            Some(microfacets) => {
+                let outgoing = -incident_ray.direction;
+                let normal =
+                    microfacets.sample_normal(hit.normal, outgoing, diffusion_sequence.next());
+                let cosine_incoming = incident_ray.direction.reflect_about(normal).dot(hit.normal);
+                if cosine_incoming <= 0.0 {
+                    // Reflected below the surface, so the path gets absorbed, like in the refraction:
+                    let ray = Ray::new(hit.location, incident_ray.direction.reflect_about(normal));
+                    return Some((ray, Bare::ZERO));
+                }
+                attenuation *=
+                    Bare::from(microfacets.weight(outgoing.dot(hit.normal), cosine_incoming));
+                normal
+            }
+            None => hit.normal,
+        };
+
+        let mut ray = Ray::new(hit.location, incident_ray.direction.reflect_about(normal));
         if let Some(fuzz) = reflectance.fuzz {
             ray.direction =
                 (ray.direction + Vec3::sample_unit_vector(diffusion_sequence) * fuzz).normalize();
         }
-        let attenuation = reflectance.attenuation.at(wavelength);
+        if let Some(conductor) = &reflectance.conductor {
+            let cosine_theta = -normal.dot(incident_ray.direction);
+            attenuation *= match &hit.material.coating {
+                Some(coating) => coating.reflectance(
+                    hit.location,
+                    wavelength,
+                    1.0,
+                    conductor.index_at(wavelength),
+                    cosine_theta,
+                ),
+                None => conductor.reflectance(wavelength, cosine_theta),
+            };
+        }
         Some((ray, attenuation))
     }
 }
@@ -1,41 +1,328 @@
 use std::fmt::{Debug, Formatter};
 use std::ops::Range;
 
+use clap::ValueEnum;
+
+use crate::args::BvhOptions;
 use crate::math::aabb::{Aabb, Bounded};
 use crate::math::hit::{Hit, Hittable};
 use crate::math::ray::Ray;
 use crate::math::sequence::Sequence;
 use crate::math::vec3::Vec3;
 
+/// Traversal stack size, which is checked against the tree depth when the hierarchy gets built.
+///
+/// The builders bound the depth by [`Tree::MAX_SAH_DEPTH`] plus the binary logarithm
+/// of the number of surfaces.
+const STACK_SIZE: usize = 2 * Tree::MAX_SAH_DEPTH;
+
 /// Bounding volume hierarchy.
-pub enum Bvh<'a, T> {
-    Empty,
-    Leaf(&'a [T]),
-    Node(Box<Node<'a, T>>),
+///
+/// It owns the surfaces, and the nodes refer to them by their indices.
+pub struct Bvh<T> {
+    surfaces: Vec<T>,
+    aabb: Aabb,
+
+    /// Linearised tree in the depth-first order, the root goes first.
+    ///
+    /// Empty for an empty hierarchy.
+    nodes: Vec<FlatNode>,
+}
+
+/// Hierarchy construction algorithm.
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Builder {
+    /// Sort the surfaces along the longest axis and split them by the median.
+    Median,
+
+    /// Choose the split with the lowest [surface area heuristic][1] cost among the binned candidates.
+    ///
+    /// [1]: https://www.pbr-book.org/3ed-2018/Primitives_and_Intersection_Acceleration/Bounding_Volume_Hierarchies#TheSurfaceAreaHeuristic
+    Sah,
+}
+
+/// Tree quality report.
+#[derive(Debug, Default)]
+pub struct Statistics {
+    pub depth: usize,
+    pub n_nodes: usize,
+    pub n_leaves: usize,
+    pub max_leaf_size: usize,
+
+    /// Estimated surface area heuristic cost of the tree.
+    pub sah_cost: f64,
 }
 
-#[derive(Debug)]
-pub struct Node<'a, T> {
+/// Intermediate tree, which is only used while building the hierarchy.
+enum Tree {
+    Leaf(Range<usize>),
+    Node(Box<Node>),
+}
+
+struct Node {
+    aabb: Aabb,
+    left: Tree,
+    right: Tree,
+}
+
+struct FlatNode {
     aabb: Aabb,
-    left: Bvh<'a, T>,
-    right: Bvh<'a, T>,
+    kind: FlatNodeKind,
+}
+
+enum FlatNodeKind {
+    /// Range of the surfaces.
+    Leaf(Range<usize>),
+
+    /// The left child immediately follows its parent, so only the right child index is stored.
+    Node { right: usize },
+}
+
+impl<T: Bounded> Bvh<T> {
+    pub fn new(mut surfaces: Vec<T>, options: &BvhOptions) -> Self {
+        let aabb = surfaces
+            .iter()
+            .map(Bounded::aabb)
+            .reduce(|accumulator, aabb| accumulator | aabb)
+            .unwrap_or(Aabb {
+                min_point: Vec3::ZERO,
+                max_point: Vec3::ZERO,
+            });
+        let mut nodes = Vec::new();
+        if !surfaces.is_empty() {
+            Tree::new(&mut surfaces, options).flatten(&surfaces, &mut nodes);
+        }
+        let bvh = Self { surfaces, aabb, nodes };
+
+        // The traversal leaves at most one pending node per level on the stack:
+        let depth = bvh.statistics().depth;
+        assert!(depth <= STACK_SIZE, "the hierarchy is too deep to traverse: {depth}");
+        bvh
+    }
+}
+
+impl<T> Bvh<T> {
+    #[inline]
+    pub fn surfaces(&self) -> &[T] {
+        &self.surfaces
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        let mut statistics = Statistics::default();
+        if !self.nodes.is_empty() {
+            self.collect_statistics(0, 1, self.aabb.surface_area(), &mut statistics);
+        }
+        statistics
+    }
+
+    fn collect_statistics(
+        &self,
+        index: usize,
+        depth: usize,
+        root_area: f64,
+        statistics: &mut Statistics,
+    ) {
+        statistics.depth = statistics.depth.max(depth);
+        let node = &self.nodes[index];
+        let relative_area = node.aabb.surface_area() / root_area;
+        match node.kind {
+            FlatNodeKind::Leaf(ref range) => {
+                statistics.n_leaves += 1;
+                statistics.max_leaf_size = statistics.max_leaf_size.max(range.len());
+                statistics.sah_cost += relative_area * range.len() as f64;
+            }
+            FlatNodeKind::Node { right } => {
+                statistics.n_nodes += 1;
+                statistics.sah_cost += relative_area * Tree::TRAVERSAL_COST;
+                self.collect_statistics(index + 1, depth + 1, root_area, statistics);
+                self.collect_statistics(right, depth + 1, root_area, statistics);
+            }
+        }
+    }
+
+    fn fmt_node(&self, index: usize, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let node = &self.nodes[index];
+        match node.kind {
+            FlatNodeKind::Leaf(ref range) => write!(f, "Leaf[{}]", range.len()),
+            FlatNodeKind::Node { right } => {
+                write!(f, "Node[{}, {}](left: ", node.aabb.min_point, node.aabb.max_point)?;
+                self.fmt_node(index + 1, f)?;
+                write!(f, ", right: ")?;
+                self.fmt_node(right, f)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// An empty hierarchy has a degenerate box at the origin.
+impl<T> Bounded for Bvh<T> {
+    #[inline]
+    fn aabb(&self) -> Aabb {
+        self.aabb
+    }
 }
 
-impl<'a, T: Bounded> Bvh<'a, T> {
-    pub fn new(surfaces: &'a mut [T], max_leaf_size: usize) -> Self {
-        if surfaces.is_empty() {
-            return Self::Empty;
+impl Tree {
+    /// Maximal depth, after which the surface area heuristic builder resorts to the median split.
+    ///
+    /// The median split halves the number of surfaces, so the tree depth stays bounded.
+    const MAX_SAH_DEPTH: usize = 32;
+    /// Number of the split candidates per axis for the surface area heuristic.
+    const N_BINS: usize = 16;
+    /// Relative cost of a node traversal, with respect to a surface intersection.
+    const TRAVERSAL_COST: f64 = 0.125;
+
+    /// Build the tree for the non-empty surfaces.
+    fn new<T: Bounded>(surfaces: &mut [T], options: &BvhOptions) -> Self {
+        // Each leaf should contain at least one surface, otherwise the split never ends:
+        let max_leaf_size = options.max_leaf_size.max(1);
+        match options.builder {
+            Builder::Median => Self::new_median(surfaces, 0, max_leaf_size, 1),
+            Builder::Sah => Self::new_sah(surfaces, 0, max_leaf_size, 1),
         }
+    }
 
+    /// Build the tree for the surfaces, which start at the `offset` in the hierarchy.
+    fn new_median<T: Bounded>(
+        surfaces: &mut [T],
+        offset: usize,
+        max_leaf_size: usize,
+        depth: usize,
+    ) -> Self {
         if surfaces.len() <= max_leaf_size {
-            return Self::Leaf(surfaces);
+            return Self::Leaf(offset..(offset + surfaces.len()));
         }
 
-        // Find out the AABB that encompasses all the surfaces.
-        let aabb = surfaces[1..]
+        let aabb = Self::union(surfaces);
+        Self::split_by_median(surfaces, aabb, offset, max_leaf_size, depth, Self::new_median)
+    }
+
+    /// Build the tree for the surfaces using the binned surface area heuristic:
+    /// <https://www.sci.utah.edu/~wald/Publications/2007/ParallelBVHBuild/fastbuild.pdf>.
+    fn new_sah<T: Bounded>(
+        surfaces: &mut [T],
+        offset: usize,
+        max_leaf_size: usize,
+        depth: usize,
+    ) -> Self {
+        if depth >= Self::MAX_SAH_DEPTH {
+            return Self::new_median(surfaces, offset, max_leaf_size, depth);
+        }
+
+        if surfaces.len() == 1 {
+            return Self::Leaf(offset..(offset + 1));
+        }
+
+        let aabb = Self::union(surfaces);
+        let centroid_aabb = surfaces
             .iter()
-            .map(|surface| surface.aabb())
-            .fold(surfaces[0].aabb(), |accumulator, aabb| accumulator | aabb);
+            .map(|surface| {
+                let center = surface.aabb().center();
+                Aabb {
+                    min_point: center,
+                    max_point: center,
+                }
+            })
+            .reduce(|accumulator, aabb| accumulator | aabb)
+            .unwrap();
+
+        let Some((axis, n_left, split_cost)) =
+            Self::find_sah_split(surfaces, &aabb, &centroid_aabb)
+        else {
+            // The centroids are not distinguishable, or the areas are infinite:
+            return if surfaces.len() <= max_leaf_size {
+                Self::Leaf(offset..(offset + surfaces.len()))
+            } else {
+                Self::split_by_median(surfaces, aabb, offset, max_leaf_size, depth, Self::new_sah)
+            };
+        };
+
+        if surfaces.len() <= max_leaf_size && surfaces.len() as f64 <= split_cost {
+            return Self::Leaf(offset..(offset + surfaces.len()));
+        }
+
+        surfaces.sort_unstable_by(|lhs, rhs| {
+            Self::axis(lhs.aabb().center(), axis).total_cmp(&Self::axis(rhs.aabb().center(), axis))
+        });
+        let (left, right) = surfaces.split_at_mut(n_left);
+        Self::Node(Box::new(Node {
+            aabb,
+            left: Self::new_sah(left, offset, max_leaf_size, depth + 1),
+            right: Self::new_sah(right, offset + n_left, max_leaf_size, depth + 1),
+        }))
+    }
+
+    /// Find the cheapest split among the bin boundaries along all the axes.
+    ///
+    /// Returns the axis, the number of surfaces to the left, and the split cost
+    /// relative to the cost of a single intersection.
+    fn find_sah_split<T: Bounded>(
+        surfaces: &[T],
+        aabb: &Aabb,
+        centroid_aabb: &Aabb,
+    ) -> Option<(usize, usize, f64)> {
+        let area = aabb.surface_area();
+        if !area.is_finite() || area <= 0.0 {
+            return None;
+        }
+
+        let mut best_split: Option<(usize, usize, f64)> = None;
+        for axis in 0..3 {
+            let min = Self::axis(centroid_aabb.min_point, axis);
+            let extent = Self::axis(centroid_aabb.max_point, axis) - min;
+            if extent <= 0.0 || !extent.is_finite() {
+                continue;
+            }
+
+            let mut bins: [(Option<Aabb>, usize); Self::N_BINS] = [(None, 0); Self::N_BINS];
+            for surface in surfaces {
+                let surface_aabb = surface.aabb();
+                let position = (Self::axis(surface_aabb.center(), axis) - min) / extent;
+                let bin =
+                    &mut bins[((position * Self::N_BINS as f64) as usize).min(Self::N_BINS - 1)];
+                bin.0 = Some(bin.0.map_or(surface_aabb, |aabb| aabb | surface_aabb));
+                bin.1 += 1;
+            }
+
+            // Sweep from the right to accumulate the right-hand side areas and counts:
+            let mut right_costs = [0.0; Self::N_BINS];
+            let mut right_aabb: Option<Aabb> = None;
+            let mut n_right = 0;
+            for i in (1..Self::N_BINS).rev() {
+                right_aabb = Self::merge(right_aabb, bins[i].0);
+                n_right += bins[i].1;
+                right_costs[i] =
+                    right_aabb.map_or(0.0, |aabb| aabb.surface_area()) * n_right as f64;
+            }
+
+            // And now sweep from the left to evaluate the splits:
+            let mut left_aabb: Option<Aabb> = None;
+            let mut n_left = 0;
+            for i in 0..(Self::N_BINS - 1) {
+                left_aabb = Self::merge(left_aabb, bins[i].0);
+                n_left += bins[i].1;
+                if n_left == 0 || n_left == surfaces.len() {
+                    continue;
+                }
+                let left_cost = left_aabb.map_or(0.0, |aabb| aabb.surface_area()) * n_left as f64;
+                let cost = Self::TRAVERSAL_COST + (left_cost + right_costs[i + 1]) / area;
+                if !best_split.is_some_and(|(_, _, best_cost)| best_cost <= cost) {
+                    best_split = Some((axis, n_left, cost));
+                }
+            }
+        }
+        best_split
+    }
+
+    fn split_by_median<T: Bounded>(
+        surfaces: &mut [T],
+        aabb: Aabb,
+        offset: usize,
+        max_leaf_size: usize,
+        depth: usize,
+        new: fn(&mut [T], usize, usize, usize) -> Self,
+    ) -> Self {
         let size = aabb.size();
 
         // Sort by the maximal dimension:
@@ -51,61 +338,311 @@ impl<'a, T: Bounded> Bvh<'a, T> {
         });
 
         // Split by the median:
-        let (left, right) = surfaces.split_at_mut(surfaces.len() / 2);
+        let middle = surfaces.len() / 2;
+        let (left, right) = surfaces.split_at_mut(middle);
 
         Self::Node(Box::new(Node {
             aabb,
-            left: Bvh::new(left, max_leaf_size),
-            right: Bvh::new(right, max_leaf_size),
+            left: new(left, offset, max_leaf_size, depth + 1),
+            right: new(right, offset + middle, max_leaf_size, depth + 1),
         }))
     }
-}
 
-impl<'a, T: Hittable<S>, S: Sequence<f64>> Hittable<S> for Bvh<'a, T> {
-    fn hit(&self, by_ray: &Ray, distance_range: &Range<f64>, rng: &mut S) -> Option<Hit> {
+    /// Find out the AABB that encompasses all the surfaces.
+    fn union<T: Bounded>(surfaces: &[T]) -> Aabb {
+        surfaces[1..]
+            .iter()
+            .map(|surface| surface.aabb())
+            .fold(surfaces[0].aabb(), |accumulator, aabb| accumulator | aabb)
+    }
+
+    #[inline]
+    fn merge(lhs: Option<Aabb>, rhs: Option<Aabb>) -> Option<Aabb> {
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => Some(lhs | rhs),
+            (lhs, None) => lhs,
+            (None, rhs) => rhs,
+        }
+    }
+
+    #[inline]
+    const fn axis(vec: Vec3, axis: usize) -> f64 {
+        match axis {
+            0 => vec.x,
+            1 => vec.y,
+            _ => vec.z,
+        }
+    }
+
+    /// Append the nodes in the depth-first order.
+    fn flatten<T: Bounded>(self, surfaces: &[T], nodes: &mut Vec<FlatNode>) {
         match self {
-            Self::Empty => None,
+            Self::Leaf(range) => nodes.push(FlatNode {
+                aabb: Self::union(&surfaces[range.clone()]),
+                kind: FlatNodeKind::Leaf(range),
+            }),
+            Self::Node(node) => {
+                let index = nodes.len();
+                nodes.push(FlatNode {
+                    aabb: node.aabb,
+                    kind: FlatNodeKind::Node { right: 0 },
+                });
+                node.left.flatten(surfaces, nodes);
+                nodes[index].kind = FlatNodeKind::Node { right: nodes.len() };
+                node.right.flatten(surfaces, nodes);
+            }
+        }
+    }
 
+    /// Recursive traversal, which always visits the both children.
+    ///
+    /// Only kept as the reference for the flattened traversal.
+    #[cfg(test)]
+    fn hit<'a, T: Hittable<S> + Bounded, S: Sequence<f64>>(
+        &self,
+        surfaces: &'a [T],
+        by_ray: &Ray,
+        distance_range: &Range<f64>,
+        rng: &mut S,
+    ) -> Option<Hit<'a>> {
+        match self {
             // For a leaf run the good old sequential search.
-            Self::Leaf(surfaces) => surfaces
+            Self::Leaf(range) => surfaces[range.clone()]
                 .iter()
                 .filter_map(|surface| surface.hit(by_ray, distance_range, rng))
                 .min_by(|hit_1, hit_2| hit_1.distance.total_cmp(&hit_2.distance)),
 
             // For a node, delegate the checks to the child nodes.
             Self::Node(node) => {
-                if node.aabb.hit(by_ray, distance_range).is_some() {
-                    let left_hit = node.left.hit(by_ray, distance_range, rng);
-                    let right_hit = node.right.hit(by_ray, distance_range, rng);
-                    match (left_hit, right_hit) {
-                        (Some(left_hit), Some(right_hit)) => {
-                            if left_hit.distance < right_hit.distance {
-                                Some(left_hit)
+                node.aabb.hit(by_ray, distance_range)?;
+                let left_hit = node.left.hit(surfaces, by_ray, distance_range, rng);
+                let right_hit = node.right.hit(surfaces, by_ray, distance_range, rng);
+                match (left_hit, right_hit) {
+                    (Some(left_hit), Some(right_hit)) => {
+                        if left_hit.distance < right_hit.distance {
+                            Some(left_hit)
+                        } else {
+                            Some(right_hit)
+                        }
+                    }
+                    (left_hit @ Some(_), None) => left_hit,
+                    (_, right_hit) => right_hit,
+                }
+            }
+        }
+    }
+}
+
+impl<T: Hittable<S>, S: Sequence<f64>> Hittable<S> for Bvh<T> {
+    /// Traverse the nodes front-to-back with an explicit stack.
+    ///
+    /// The nearer child is visited first, and the search range shrinks with every hit found,
+    /// so that the farther nodes get culled by their boxes.
+    fn hit(&self, by_ray: &Ray, distance_range: &Range<f64>, rng: &mut S) -> Option<Hit> {
+        let root = self.nodes.first()?;
+        let mut distance_range = distance_range.clone();
+        let (entry_distance, _) = root.aabb.hit(by_ray, &distance_range)?;
+
+        // Pending node indices along with their box entry distances:
+        let mut stack = [(0, 0.0); STACK_SIZE];
+        stack[0] = (0, entry_distance);
+        let mut stack_size = 1;
+
+        let mut closest_hit = None;
+        while stack_size != 0 {
+            stack_size -= 1;
+            let (index, entry_distance) = stack[stack_size];
+            if entry_distance >= distance_range.end {
+                // A closer hit has been found since the node was pushed.
+                continue;
+            }
+
+            match self.nodes[index].kind {
+                FlatNodeKind::Leaf(ref range) => {
+                    for surface in &self.surfaces[range.clone()] {
+                        if let Some(hit) = surface.hit(by_ray, &distance_range, rng) {
+                            distance_range.end = hit.distance;
+                            closest_hit = Some(hit);
+                        }
+                    }
+                }
+                FlatNodeKind::Node { right } => {
+                    let left = index + 1;
+                    let left_hit = self.nodes[left].aabb.hit(by_ray, &distance_range);
+                    let right_hit = self.nodes[right].aabb.hit(by_ray, &distance_range);
+
+                    // Push the farther child first, so that the nearer one gets popped first:
+                    let children = match (left_hit, right_hit) {
+                        (Some((left_distance, _)), Some((right_distance, _))) => {
+                            if left_distance <= right_distance {
+                                [Some((right, right_distance)), Some((left, left_distance))]
                             } else {
-                                Some(right_hit)
+                                [Some((left, left_distance)), Some((right, right_distance))]
                             }
                         }
-                        (left_hit @ Some(_), None) => left_hit,
-                        (_, right_hit) => right_hit,
+                        (Some((left_distance, _)), None) => [Some((left, left_distance)), None],
+                        (None, Some((right_distance, _))) => [Some((right, right_distance)), None],
+                        (None, None) => [None, None],
+                    };
+                    for child in children.into_iter().flatten() {
+                        stack[stack_size] = child;
+                        stack_size += 1;
                     }
-                } else {
-                    None
                 }
             }
         }
+        closest_hit
     }
 }
 
-impl<'a, T> Debug for Bvh<'a, T> {
+impl<T> Debug for Bvh<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Empty => write!(f, "Empty"),
-            Self::Leaf(leaf) => write!(f, "Leaf[{}]", leaf.len()),
-            Self::Node(node) => write!(
-                f,
-                "Node[{}, {}](left: {:?}, right: {:?})",
-                node.aabb.min_point, node.aabb.max_point, node.left, node.right
-            ),
+        if self.nodes.is_empty() {
+            write!(f, "Empty")
+        } else {
+            self.fmt_node(0, f)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use std::f64::consts::{PI, TAU};
+    use std::sync::Arc;
+
+    use test::Bencher;
+
+    use super::*;
+    use crate::math::sequence::RandomSequence;
+    use crate::surface::triangle::Triangle;
+
+    struct Box(Aabb);
+
+    impl Bounded for Box {
+        fn aabb(&self) -> Aabb {
+            self.0
+        }
+    }
+
+    #[test]
+    fn sah_is_cheaper_ok() {
+        let [median, sah] = [Builder::Median, Builder::Sah].map(|builder| {
+            // Huge «ground» box next to a row of small boxes:
+            let mut boxes = vec![Box(Aabb {
+                min_point: Vec3::new(-1000.0, -2000.0, -1000.0),
+                max_point: Vec3::new(1000.0, 0.0, 1000.0),
+            })];
+            boxes.extend((0..100).map(|i| {
+                let min_point = Vec3::new(i as f64, 0.0, 0.0);
+                Box(Aabb {
+                    min_point,
+                    max_point: min_point + 0.5,
+                })
+            }));
+            Bvh::new(boxes, &BvhOptions { max_leaf_size: 4, builder }).statistics()
+        });
+        assert!(sah.sah_cost < median.sah_cost, "SAH: {sah:?}, median: {median:?}");
+        assert!(sah.max_leaf_size <= 4);
+    }
+
+    /// Tessellated unit sphere with 2 × 64 × 128 triangles.
+    fn new_triangles() -> Vec<Triangle> {
+        const N_RINGS: usize = 64;
+        const N_SEGMENTS: usize = 128;
+
+        let point = |ring: usize, segment: usize| {
+            let (sin_theta, cos_theta) = (PI * ring as f64 / N_RINGS as f64).sin_cos();
+            // Wrap around, so that the seam has no cracks:
+            let segment = segment % N_SEGMENTS;
+            let (sin_phi, cos_phi) = (TAU * segment as f64 / N_SEGMENTS as f64).sin_cos();
+            Vec3::new(sin_theta * cos_phi, sin_theta * sin_phi, cos_theta)
+        };
+        let material = Arc::default();
+        let mut triangles = Vec::with_capacity(2 * N_RINGS * N_SEGMENTS);
+        for ring in 0..N_RINGS {
+            for segment in 0..N_SEGMENTS {
+                let corners = [
+                    point(ring, segment),
+                    point(ring + 1, segment),
+                    point(ring + 1, segment + 1),
+                    point(ring, segment + 1),
+                ];
+                triangles.push(Triangle::new(
+                    [corners[0], corners[1], corners[2]],
+                    Arc::clone(&material),
+                    false,
+                ));
+                triangles.push(Triangle::new(
+                    [corners[0], corners[2], corners[3]],
+                    Arc::clone(&material),
+                    false,
+                ));
+            }
+        }
+        triangles
+    }
+
+    /// Rays from a circle around the sphere towards its near side.
+    fn new_rays() -> Vec<Ray> {
+        (0..1000)
+            .map(|i| {
+                let angle = TAU * (i as f64 + 0.5) / 1000.0;
+                let origin = Vec3::new(3.0 * angle.cos(), 3.0 * angle.sin(), 0.5);
+                Ray::with_two_points(origin, Vec3::new(0.0, 0.0, (angle * 7.0).sin() * 0.9))
+            })
+            .collect()
+    }
+
+    const OPTIONS: BvhOptions = BvhOptions {
+        max_leaf_size: 4,
+        builder: Builder::Sah,
+    };
+
+    #[test]
+    fn flattened_hit_ok() {
+        let bvh = Bvh::new(new_triangles(), &OPTIONS);
+        let mut triangles = new_triangles();
+        let tree = Tree::new(&mut triangles, &OPTIONS);
+        let mut rng = RandomSequence::new();
+
+        for ray in new_rays() {
+            let expected = tree
+                .hit(&triangles, &ray, &(0.0..f64::INFINITY), &mut rng)
+                .unwrap()
+                .distance;
+            let actual = bvh
+                .hit(&ray, &(0.0..f64::INFINITY), &mut rng)
+                .unwrap()
+                .distance;
+            assert_eq!(actual, expected);
         }
     }
+
+    /// Baseline for [`bench_flattened_hit`]: the recursive traversal of the same tree.
+    #[bench]
+    fn bench_recursive_hit(bencher: &mut Bencher) {
+        let mut triangles = new_triangles();
+        let tree = Tree::new(&mut triangles, &OPTIONS);
+        let rays = new_rays();
+        let mut rng = RandomSequence::new();
+        bencher.iter(|| {
+            for ray in &rays {
+                test::black_box(tree.hit(&triangles, ray, &(0.0..f64::INFINITY), &mut rng));
+            }
+        });
+    }
+
+    #[bench]
+    fn bench_flattened_hit(bencher: &mut Bencher) {
+        let bvh = Bvh::new(new_triangles(), &OPTIONS);
+        let rays = new_rays();
+        let mut rng = RandomSequence::new();
+        bencher.iter(|| {
+            for ray in &rays {
+                test::black_box(bvh.hit(ray, &(0.0..f64::INFINITY), &mut rng));
+            }
+        });
+    }
 }
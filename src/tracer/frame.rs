@@ -0,0 +1,326 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use rayon::prelude::*;
+
+use crate::args::{BvhOptions, TracerOptions};
+use crate::color::observer::Observer;
+use crate::color::xyz::XyzColor;
+use crate::math::vec3::Vec3;
+use crate::prelude::*;
+
+/// Accumulated samples of the image being rendered.
+///
+/// It can be written to, and read from, a checkpoint file, so that the rendering may be resumed.
+pub struct Frame {
+    width: u32,
+    height: u32,
+
+    /// Fingerprint of the scene and the tracing options, which the pixels depend on.
+    fingerprint: u64,
+
+    /// Row-major pixels.
+    pixels: Vec<Pixel>,
+}
+
+#[derive(Copy, Clone)]
+pub struct Pixel {
+    /// Sum of the XYZ estimates of all the samples.
+    pub sum: XyzColor,
+
+    pub n_samples: u32,
+}
+
+impl Default for Pixel {
+    fn default() -> Self {
+        Self {
+            sum: XyzColor::from(Vec3::ZERO),
+            n_samples: 0,
+        }
+    }
+}
+
+impl Frame {
+    /// Checkpoint file signature, including the format version.
+    const MAGIC: &'static [u8; 8] = b"RTFRAME2";
+
+    pub fn new(width: u32, height: u32, fingerprint: u64) -> Self {
+        Self {
+            width,
+            height,
+            fingerprint,
+            pixels: vec![Pixel::default(); width as usize * height as usize],
+        }
+    }
+
+    /// Read the checkpoint.
+    ///
+    /// The checkpoint consists of the signature, the little-endian `u32` width and height,
+    /// the `u64` fingerprint, and the row-major pixels: the `f64` XYZ sums followed by the `u32` sample count.
+    pub fn read_from(path: &Path) -> Result<Self> {
+        let mut reader =
+            BufReader::new(File::open(path).with_context(|| format!("failed to open `{path:?}`"))?);
+
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != Self::MAGIC {
+            bail!("`{path:?}` is not a checkpoint file");
+        }
+        let width = Self::read_u32(&mut reader)?;
+        let height = Self::read_u32(&mut reader)?;
+        let fingerprint = Self::read_u64(&mut reader)?;
+
+        let mut frame = Self::new(width, height, fingerprint);
+        for pixel in &mut frame.pixels {
+            let x = Self::read_f64(&mut reader)?;
+            let y = Self::read_f64(&mut reader)?;
+            let z = Self::read_f64(&mut reader)?;
+            *pixel = Pixel {
+                sum: XyzColor::from(Vec3::new(x, y, z)),
+                n_samples: Self::read_u32(&mut reader)?,
+            };
+        }
+        Ok(frame)
+    }
+
+    /// Write the checkpoint into a temporary file, and then move it over the original one,
+    /// so that an interruption never leaves a broken checkpoint.
+    pub fn write_to(&self, path: &Path) -> Result {
+        let temporary_path = path.with_extension("tmp");
+        {
+            let file = File::create(&temporary_path)
+                .with_context(|| format!("failed to create `{temporary_path:?}`"))?;
+            let mut writer = BufWriter::new(file);
+            writer.write_all(Self::MAGIC)?;
+            writer.write_all(&self.width.to_le_bytes())?;
+            writer.write_all(&self.height.to_le_bytes())?;
+            writer.write_all(&self.fingerprint.to_le_bytes())?;
+            for pixel in &self.pixels {
+                let sum = Vec3::from(pixel.sum);
+                writer.write_all(&sum.x.to_le_bytes())?;
+                writer.write_all(&sum.y.to_le_bytes())?;
+                writer.write_all(&sum.z.to_le_bytes())?;
+                writer.write_all(&pixel.n_samples.to_le_bytes())?;
+            }
+            writer.flush()?;
+        }
+        fs::rename(&temporary_path, path)
+            .with_context(|| format!("failed to move `{temporary_path:?}` to `{path:?}`"))
+    }
+
+    #[inline]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub const fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// [FNV-1a][1] hash of the scene file and the files it refers to, the observer,
+    /// and those options, which change the pixels, so that a checkpoint is only resumed with the same ones.
+    ///
+    /// The sample counts are left out, so that more samples may be added to a checkpoint.
+    ///
+    /// [1]: https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function
+    pub fn fingerprint_of(
+        files: &[Vec<u8>],
+        observer: Observer,
+        tracer_options: &TracerOptions,
+        bvh_options: &BvhOptions,
+    ) -> u64 {
+        let options = [
+            u64::from(observer as u8),
+            u64::from(tracer_options.n_max_bounces),
+            tracer_options.min_hit_distance.to_bits(),
+            tracer_options.min_attenuation.to_bits(),
+            u64::from(tracer_options.roulette_depth),
+            tracer_options.roulette_survival_probability.to_bits(),
+            bvh_options.max_leaf_size as u64,
+            bvh_options.builder as u64,
+        ];
+        files
+            .iter()
+            // Prefix each file with its length, so that the bytes can't move between the files:
+            .flat_map(|file| {
+                (file.len() as u64)
+                    .to_le_bytes()
+                    .into_iter()
+                    .chain(file.iter().copied())
+            })
+            .chain(options.iter().flat_map(|option| option.to_le_bytes()))
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// Minimal number of samples over all the pixels.
+    pub fn min_n_samples(&self) -> u32 {
+        self.pixels
+            .iter()
+            .map(|pixel| pixel.n_samples)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Iterate over the rows in parallel, along with their Y-coordinates.
+    pub fn par_rows_mut(&mut self) -> impl IndexedParallelIterator<Item = (u32, &mut [Pixel])> {
+        self.pixels
+            .par_chunks_mut(self.width as usize)
+            .enumerate()
+            .map(|(y, row)| (y as u32, row))
+    }
+
+    /// Average the samples of each pixel.
+    pub fn to_rows(&self) -> Vec<(u32, Vec<XyzColor>)> {
+        self.pixels
+            .chunks(self.width as usize)
+            .enumerate()
+            .map(|(y, row)| {
+                let row = row
+                    .iter()
+                    .map(|pixel| pixel.sum / f64::from(pixel.n_samples.max(1)))
+                    .collect();
+                (y as u32, row)
+            })
+            .collect()
+    }
+
+    fn read_u32(reader: &mut impl Read) -> Result<u32> {
+        let mut buffer = [0; 4];
+        reader.read_exact(&mut buffer)?;
+        Ok(u32::from_le_bytes(buffer))
+    }
+
+    fn read_u64(reader: &mut impl Read) -> Result<u64> {
+        let mut buffer = [0; 8];
+        reader.read_exact(&mut buffer)?;
+        Ok(u64::from_le_bytes(buffer))
+    }
+
+    fn read_f64(reader: &mut impl Read) -> Result<f64> {
+        let mut buffer = [0; 8];
+        reader.read_exact(&mut buffer)?;
+        Ok(f64::from_le_bytes(buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::scene::Scene;
+    use crate::tracer::Tracer;
+
+    const SCENE: &str = "
+        camera = { location = [0, 0, -3] }
+        ambient_emittance = { type = 'Constant', density = 1 }
+
+        [[surfaces]]
+        type = 'Sphere'
+        center = [0, 0, 0]
+        radius = 1
+        material = { reflectance = { diffusion = 1, attenuation = { type = 'Constant' } } }
+    ";
+
+    /// Trace the frame up to the number of samples per pixel, two samples per pass.
+    fn trace(frame: &mut Frame, n_samples_per_pixel: u32) {
+        let mut scene: Scene = toml::from_str(SCENE).unwrap();
+        let bvh_options = BvhOptions::parse_from(["test"]);
+        let mut options = TracerOptions::parse_from(["test", "--pass-samples", "2"]);
+        options.n_samples_per_pixel = n_samples_per_pixel;
+        let tracer = Tracer::new(
+            scene.build_bvh(&bvh_options).unwrap(),
+            scene.ambient_emittance,
+            scene.sky,
+            Observer::default(),
+            scene.camera,
+            options,
+            frame.width(),
+            frame.height(),
+        );
+        tracer.trace(frame, |_| Ok(())).unwrap();
+    }
+
+    #[test]
+    fn write_read_ok() {
+        let mut frame = Frame::new(2, 1, 42);
+        frame.pixels[1] = Pixel {
+            sum: XyzColor::from(Vec3::new(1.0, 2.0, 3.0)),
+            n_samples: 42,
+        };
+        let path = std::env::temp_dir()
+            .join(format!("frame-write-read-{}.checkpoint", std::process::id()));
+        frame.write_to(&path).unwrap();
+        let frame = Frame::read_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!((frame.width(), frame.height()), (2, 1));
+        assert_eq!(frame.fingerprint(), 42);
+        assert_eq!(frame.min_n_samples(), 0);
+        assert_eq!(frame.pixels[1].n_samples, 42);
+        assert!(Vec3::from(frame.pixels[1].sum).abs_diff_eq(Vec3::new(1.0, 2.0, 3.0), 0.0));
+    }
+
+    /// Resuming from a checkpoint must give exactly the same pixels as the uninterrupted run.
+    #[test]
+    fn resume_ok() {
+        let mut frame = Frame::new(4, 3, 0);
+        trace(&mut frame, 3);
+        let path =
+            std::env::temp_dir().join(format!("frame-resume-{}.checkpoint", std::process::id()));
+        frame.write_to(&path).unwrap();
+        let mut resumed = Frame::read_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        trace(&mut resumed, 3 + 4);
+
+        let mut uninterrupted = Frame::new(4, 3, 0);
+        trace(&mut uninterrupted, 3 + 4);
+
+        for (resumed, uninterrupted) in resumed.pixels.iter().zip(&uninterrupted.pixels) {
+            assert_eq!(resumed.n_samples, 7);
+            assert_eq!(resumed.n_samples, uninterrupted.n_samples);
+            assert!(Vec3::from(resumed.sum).abs_diff_eq(Vec3::from(uninterrupted.sum), 0.0));
+        }
+    }
+
+    #[test]
+    fn fingerprint_ok() {
+        let files = [SCENE.as_bytes().to_vec(), b"v 0 0 0".to_vec()];
+        let options = TracerOptions::parse_from(["test"]);
+        let bvh_options = BvhOptions::parse_from(["test"]);
+        let fingerprint = |files: &[Vec<u8>], options: &TracerOptions, bvh_options: &BvhOptions| {
+            Frame::fingerprint_of(files, Observer::default(), options, bvh_options)
+        };
+        let expected = fingerprint(&files, &options, &bvh_options);
+
+        let more_samples = TracerOptions::parse_from(["test", "--samples", "100"]);
+        assert_eq!(fingerprint(&files, &more_samples, &bvh_options), expected);
+
+        let other_scene = [
+            SCENE.replace("radius = 1", "radius = 2").into_bytes(),
+            files[1].clone(),
+        ];
+        assert_ne!(fingerprint(&other_scene, &options, &bvh_options), expected);
+
+        let other_mesh = [files[0].clone(), b"v 0 0 1".to_vec()];
+        assert_ne!(fingerprint(&other_mesh, &options, &bvh_options), expected);
+
+        let fewer_bounces = TracerOptions::parse_from(["test", "--max-bounces", "2"]);
+        assert_ne!(fingerprint(&files, &fewer_bounces, &bvh_options), expected);
+
+        let other_builder = BvhOptions::parse_from(["test", "--bvh-builder", "sah"]);
+        assert_ne!(fingerprint(&files, &options, &other_builder), expected);
+
+        let other_leaf_size = BvhOptions::parse_from(["test", "--max-bvh-leaf-size", "4"]);
+        assert_ne!(fingerprint(&files, &options, &other_leaf_size), expected);
+    }
+}
@@ -0,0 +1,536 @@
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::TAU;
+
+use crate::math::hit::{Hit, HitType};
+use crate::math::transform::Transform;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::physics::optics::material::Material;
+use crate::physics::optics::sky::Sky;
+use crate::prelude::*;
+use crate::surface::sphere::Sphere;
+use crate::surface::Surface;
+
+/// Emissive surfaces, which get sampled directly for the [next-event estimation][1].
+///
+/// Spheres and triangles with the same material (for example, of the same mesh,
+/// or of the instances of the same geometry) are grouped into a single light.
+/// The instanced emitters are sampled in the world space.
+///
+/// Emissive fog, and the instanced spheres, which are not uniformly scaled, are not sampled
+/// directly: they get reported when the lights are collected, and only contribute
+/// when a scattered ray happens to hit them.
+///
+/// The sun of the sky is sampled too, and it is visible when nothing is hit.
+///
+/// [1]: https://www.pbr-book.org/3ed-2018/Light_Transport_I_Surface_Reflection/Direct_Lighting
+pub struct Lights {
+    lights: Vec<Light>,
+
+    /// Light indices by their material addresses.
+    indices: HashMap<usize, usize>,
+
+    sun_index: Option<usize>,
+}
+
+enum Light {
+    Spheres {
+        /// Centers and radii.
+        spheres: Vec<(Vec3, f64)>,
+    },
+
+    Triangles {
+        vertices: Vec<[Vec3; 3]>,
+        normals: Vec<Vec3>,
+
+        /// Running sum of the triangle areas, the last one is the total area.
+        cumulative_areas: Vec<f64>,
+    },
+
+    Sun {
+        direction: Vec3,
+        one_minus_cos_max: f64,
+    },
+}
+
+/// Direction towards a sampled light.
+pub struct LightSample {
+    pub direction: Vec3,
+
+    /// Probability density of the direction, per unit solid angle.
+    pub density: f64,
+
+    index: usize,
+
+    /// Distance to the sampled point, unless any visible point of the light would do.
+    distance: Option<f64>,
+}
+
+/// Collects the emitters from the surfaces and the instanced geometries.
+#[derive(Default)]
+struct Collector {
+    /// Lights along with their keys.
+    lights: Vec<(usize, Light)>,
+
+    /// Light indices by their keys.
+    indices: HashMap<usize, usize>,
+
+    /// Keys of the emitters, which cannot be sampled directly.
+    unsampled: HashSet<usize>,
+}
+
+impl Collector {
+    fn add(&mut self, surface: &Surface, transform: &Transform) {
+        match surface {
+            Surface::Sphere(sphere) if sphere.material.emittance.is_some() => {
+                let key = Lights::key(&sphere.material);
+                let Some(sphere) = Self::transform_sphere(sphere, transform) else {
+                    self.skip(key, "emissive sphere is not uniformly scaled");
+                    return;
+                };
+                let Light::Spheres { spheres } =
+                    self.entry(key, || Light::Spheres { spheres: Vec::new() })
+                else {
+                    unreachable!("spheres do not share the materials with triangles");
+                };
+                spheres.push(sphere);
+            }
+
+            Surface::Triangle(triangle) if triangle.material().emittance.is_some() => {
+                let Light::Triangles { vertices, normals, cumulative_areas } =
+                    self.entry(Lights::key(triangle.material()), || Light::Triangles {
+                        vertices: Vec::new(),
+                        normals: Vec::new(),
+                        cumulative_areas: Vec::new(),
+                    })
+                else {
+                    unreachable!("triangles do not share the materials with spheres");
+                };
+                let [a, b, c] = triangle
+                    .vertices()
+                    .map(|vertex| transform.transform_point(vertex));
+                let area = (b - a).cross(c - a).length() / 2.0;
+                vertices.push([a, b, c]);
+                normals.push(
+                    transform
+                        .transform_normal(triangle.face_normal())
+                        .normalize(),
+                );
+                cumulative_areas.push(cumulative_areas.last().unwrap_or(&0.0) + area);
+            }
+
+            Surface::UniformFog(fog) if fog.material.emittance.is_some() => {
+                self.skip(Lights::key(&fog.material), "emissive fog");
+            }
+            Surface::HeterogeneousFog(fog) if fog.material.emittance.is_some() => {
+                self.skip(Lights::key(&fog.material), "emissive fog");
+            }
+            Surface::BoundedFog(fog) if fog.material.emittance.is_some() => {
+                self.skip(Lights::key(&fog.material), "emissive fog");
+            }
+
+            Surface::Instance(instance) => {
+                let transform = instance.transform().then(*transform);
+                for surface in instance.bvh().surfaces() {
+                    self.add(surface, &transform);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn entry(&mut self, key: usize, new_light: impl FnOnce() -> Light) -> &mut Light {
+        let index = *self.indices.entry(key).or_insert_with(|| {
+            self.lights.push((key, new_light()));
+            self.lights.len() - 1
+        });
+        &mut self.lights[index].1
+    }
+
+    fn skip(&mut self, key: usize, reason: &str) {
+        if self.unsampled.insert(key) {
+            warn!(reason, "the emitter is not sampled directly");
+        }
+    }
+
+    /// Transform the sphere center and radius, unless the transformation distorts the sphere.
+    fn transform_sphere(sphere: &Sphere, transform: &Transform) -> Option<(Vec3, f64)> {
+        /// Relative tolerance for the transformed radii.
+        const TOLERANCE: f64 = 1e-9;
+
+        let center = transform.transform_point(sphere.center);
+        let axes = [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ]
+        .map(|axis| transform.transform_point(sphere.center + axis * sphere.radius) - center);
+        let radius = axes[0].length();
+        let tolerance = TOLERANCE * radius * radius;
+        let is_uniform = (axes[1].length_squared() - radius * radius).abs() <= tolerance
+            && (axes[2].length_squared() - radius * radius).abs() <= tolerance
+            && axes[0].dot(axes[1]).abs() <= tolerance
+            && axes[0].dot(axes[2]).abs() <= tolerance
+            && axes[1].dot(axes[2]).abs() <= tolerance;
+        is_uniform.then_some((center, radius))
+    }
+}
+
+impl Lights {
+    pub fn new(surfaces: &[Surface], sky: Option<&Sky>) -> Self {
+        let mut collector = Collector::default();
+        for surface in surfaces {
+            collector.add(surface, &Transform::IDENTITY);
+        }
+
+        // A light is only sampled, when all its parts can be sampled:
+        let mut lights = Vec::new();
+        let mut indices = HashMap::new();
+        for (key, light) in collector.lights {
+            if !collector.unsampled.contains(&key) {
+                indices.insert(key, lights.len());
+                lights.push(light);
+            }
+        }
+
+        let sun_index = sky.map(|sky| {
+            lights.push(Light::Sun {
+                direction: sky.sun_direction,
+                one_minus_cos_max: 2.0 * (Sky::SUN_ANGULAR_RADIUS / 2.0).sin().powi(2),
+            });
+            lights.len() - 1
+        });
+
+        info!(n_lights = lights.len(), "collected the lights");
+        Self { lights, indices, sun_index }
+    }
+
+    /// Pick a light with the `selection` number, and sample a direction towards it.
+    ///
+    /// The numbers are expected to be uniformly distributed within `[0, 1)`.
+    pub fn sample(&self, from: Vec3, selection: f64, point: Vec2) -> Option<LightSample> {
+        if self.lights.is_empty() {
+            return None;
+        }
+        let index = ((selection * self.lights.len() as f64) as usize).min(self.lights.len() - 1);
+
+        let (direction, distance, density) = match &self.lights[index] {
+            Light::Spheres { spheres } => {
+                // Pick a sphere with the rest of the selection number:
+                let selection = selection * self.lights.len() as f64 - index as f64;
+                let i = ((selection * spheres.len() as f64) as usize).min(spheres.len() - 1);
+
+                // Uniformly sample the cone, which encloses the sphere:
+                let (center, radius) = spheres[i];
+                let to_center = center - from;
+                let distance_squared = to_center.length_squared();
+                let sin_squared_max = radius * radius / distance_squared;
+                if sin_squared_max >= 1.0 {
+                    // The point is inside the sphere.
+                    return None;
+                }
+                let one_minus_cos_max = sin_squared_max / (1.0 + (1.0 - sin_squared_max).sqrt());
+                let axis = to_center / distance_squared.sqrt();
+                let direction = Self::sample_cone(axis, one_minus_cos_max, point);
+                (direction, None, Self::spheres_density(spheres, from, direction))
+            }
+
+            Light::Triangles { vertices, normals, cumulative_areas } => {
+                // Pick a triangle proportionally to its area, and reuse the number for the point:
+                let total_area = *cumulative_areas.last().unwrap();
+                let target = point.x * total_area;
+                let i = cumulative_areas
+                    .partition_point(|area| *area <= target)
+                    .min(cumulative_areas.len() - 1);
+                let lower_area = if i == 0 { 0.0 } else { cumulative_areas[i - 1] };
+                let u =
+                    ((target - lower_area) / (cumulative_areas[i] - lower_area)).clamp(0.0, 1.0);
+
+                // Uniformly sample the triangle:
+                let sqrt_u = u.sqrt();
+                let [a, b, c] = vertices[i];
+                let sampled_point =
+                    a * (1.0 - sqrt_u) + b * (sqrt_u * (1.0 - point.y)) + c * (sqrt_u * point.y);
+
+                let to_point = sampled_point - from;
+                let distance = to_point.length();
+                let direction = to_point / distance;
+                let cosine = -normals[i].dot(direction);
+                if cosine <= 0.0 {
+                    // We're behind the triangle, which only emits from the front.
+                    return None;
+                }
+                (direction, Some(distance), distance * distance / (total_area * cosine))
+            }
+
+            Light::Sun { direction, one_minus_cos_max } => {
+                let direction = Self::sample_cone(*direction, *one_minus_cos_max, point);
+                (direction, None, 1.0 / (TAU * one_minus_cos_max))
+            }
+        };
+
+        Some(LightSample {
+            direction,
+            density: density / self.lights.len() as f64,
+            index,
+            distance,
+        })
+    }
+
+    /// Probability density, per unit solid angle, of sampling the direction from the point
+    /// towards the hit.
+    ///
+    /// It is zero when the hit surface is not among the lights.
+    pub fn density(&self, from: Vec3, hit: &Hit) -> f64 {
+        if hit.type_ != HitType::Enter {
+            return 0.0;
+        }
+        let Some(index) = self.indices.get(&Self::key(hit.material)) else {
+            return 0.0;
+        };
+
+        let density = match &self.lights[*index] {
+            Light::Spheres { spheres } => {
+                Self::spheres_density(spheres, from, (hit.location - from) / hit.distance)
+            }
+            Light::Triangles { cumulative_areas, .. } => {
+                let direction = (hit.location - from) / hit.distance;
+                let cosine = hit.face_normal.dot(direction).abs();
+                hit.distance * hit.distance / (cumulative_areas.last().unwrap() * cosine)
+            }
+            Light::Sun { .. } => unreachable!("the sun has no material to hit"),
+        };
+        density / self.lights.len() as f64
+    }
+
+    /// Probability density, per unit solid angle, of sampling a direction within the solar disc.
+    ///
+    /// It is zero when the scene has no sky.
+    pub fn sun_density(&self) -> f64 {
+        match self.sun_index.map(|index| &self.lights[index]) {
+            Some(Light::Sun { one_minus_cos_max, .. }) => {
+                1.0 / (TAU * one_minus_cos_max) / self.lights.len() as f64
+            }
+            _ => 0.0,
+        }
+    }
+
+    #[inline]
+    pub fn is_sun(&self, sample: &LightSample) -> bool {
+        self.sun_index == Some(sample.index)
+    }
+
+    /// Check whether the hit is the sampled point of the sampled light,
+    /// and not an occluder in between.
+    pub fn is_sampled(&self, sample: &LightSample, hit: &Hit) -> bool {
+        /// Relative tolerance for the distance to the sampled point.
+        const TOLERANCE: f64 = 1e-6;
+
+        if self.indices.get(&Self::key(hit.material)) != Some(&sample.index) {
+            return false;
+        }
+        match sample.distance {
+            Some(distance) => hit.distance >= distance * (1.0 - TOLERANCE),
+            None => true,
+        }
+    }
+
+    /// Probability density of sampling the direction towards any of the spheres,
+    /// whose enclosing cones may overlap.
+    fn spheres_density(spheres: &[(Vec3, f64)], from: Vec3, direction: Vec3) -> f64 {
+        let density: f64 = spheres
+            .iter()
+            .filter_map(|(center, radius)| {
+                let to_center = *center - from;
+                let distance_squared = to_center.length_squared();
+                let sin_squared_max = radius * radius / distance_squared;
+                if sin_squared_max >= 1.0 {
+                    // The point is inside the sphere, which is never sampled then.
+                    return None;
+                }
+                let one_minus_cos_max = sin_squared_max / (1.0 + (1.0 - sin_squared_max).sqrt());
+                let one_minus_cos = 1.0 - direction.dot(to_center) / distance_squared.sqrt();
+                // The tolerance keeps the sampled directions on the cone edge:
+                (one_minus_cos <= one_minus_cos_max * (1.0 + 1e-9))
+                    .then_some(1.0 / (TAU * one_minus_cos_max))
+            })
+            .sum();
+        density / spheres.len() as f64
+    }
+
+    /// Uniformly sample a direction within the cone around the unit axis.
+    fn sample_cone(axis: Vec3, one_minus_cos_max: f64, point: Vec2) -> Vec3 {
+        let cos_theta = 1.0 - point.x * one_minus_cos_max;
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let (sin_phi, cos_phi) = (TAU * point.y).sin_cos();
+        let (tangent, bitangent) = axis.orthonormal_basis();
+        tangent * (sin_theta * cos_phi) + bitangent * (sin_theta * sin_phi) + axis * cos_theta
+    }
+
+    /// Lights are identified by their material addresses.
+    #[inline]
+    fn key(material: &Material) -> usize {
+        material as *const Material as usize
+    }
+}
+
+/// [Power heuristic][1] weight of the sampling strategy.
+///
+/// [1]: https://www.pbr-book.org/3ed-2018/Monte_Carlo_Integration/Importance_Sampling#MultipleImportanceSampling
+#[inline]
+pub fn power_heuristic(density: f64, other_density: f64) -> f64 {
+    let density_squared = density * density;
+    let sum = density_squared + other_density * other_density;
+    if sum > 0.0 {
+        density_squared / sum
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::args::BvhOptions;
+    use crate::math::hit::Hittable;
+    use crate::math::ray::Ray;
+    use crate::math::sequence::RandomSequence;
+    use crate::tracer::bvh::{Builder, Bvh};
+
+    /// The sampled density must match the density of the hit in the same direction.
+    #[test]
+    fn density_ok() {
+        let scene = r#"
+            [[surfaces]]
+            type = "Sphere"
+            center = [0, 0, 10]
+            radius = 2
+            material = { emittance = { type = "BlackBody", temperature = 5000 } }
+
+            [[surfaces]]
+            type = "Triangle"
+            vertices = [[-1, -1, -5], [1, -1, -5], [0, 1, -5]]
+            material = { emittance = { type = "BlackBody", temperature = 5000 } }
+
+            # Smooth-shaded, so that the shading normals differ from the face normal:
+            [[surfaces]]
+            type = "Triangle"
+            vertices = [[5, -1, -1], [5, -1, 1], [5, 1, 0]]
+            normals = [[-1, 1, 0], [-1, -1, 0], [-1, 0, 1]]
+            material = { emittance = { type = "BlackBody", temperature = 5000 } }
+        "#;
+        #[derive(serde::Deserialize)]
+        struct Scene {
+            surfaces: Vec<Surface>,
+        }
+        let surfaces = toml::from_str::<Scene>(scene).unwrap().surfaces;
+        let lights = Lights::new(&surfaces, None);
+        assert_eq!(lights.lights.len(), 3);
+
+        let mut rng = RandomSequence::new();
+        for (selection, point) in [
+            (0.2, Vec2::new(0.3, 0.7)),
+            (0.5, Vec2::new(0.6, 0.2)),
+            (0.8, Vec2::new(0.4, 0.5)),
+        ] {
+            let sample = lights.sample(Vec3::ZERO, selection, point).unwrap();
+            let ray = Ray::new(Vec3::ZERO, sample.direction);
+            let hit = surfaces
+                .iter()
+                .find_map(|surface| surface.hit(&ray, &(0.0..f64::INFINITY), &mut rng))
+                .unwrap();
+            assert!(lights.is_sampled(&sample, &hit));
+            assert_abs_diff_eq!(
+                lights.density(Vec3::ZERO, &hit),
+                sample.density,
+                epsilon = 1e-9 * sample.density,
+            );
+        }
+    }
+
+    #[test]
+    fn sun_ok() {
+        let sky: Sky = toml::from_str("sun_elevation = 30\nsun_azimuth = 45").unwrap();
+        let sphere = r#"
+            type = "Sphere"
+            center = [0, 0, 10]
+            radius = 2
+            material = { emittance = { type = "BlackBody", temperature = 5000 } }
+        "#;
+        let sphere: Surface = toml::from_str(sphere).unwrap();
+        let lights = Lights::new(&[sphere], Some(&sky));
+        assert_eq!(lights.lights.len(), 2);
+
+        let sample = lights.sample(Vec3::ZERO, 0.9, Vec2::new(0.3, 0.7)).unwrap();
+        assert!(lights.is_sun(&sample));
+        assert_abs_diff_eq!(sample.density, lights.sun_density(), epsilon = 1e-9 * sample.density);
+        let cosine = sample.direction.dot(sky.sun_direction);
+        assert!(cosine >= Sky::SUN_ANGULAR_RADIUS.cos(), "cosine: {cosine}");
+    }
+
+    /// Instanced emitters are sampled in the world space,
+    /// and the instances of the same geometry make a single light.
+    #[test]
+    fn instances_ok() {
+        let sphere = r#"
+            type = "Sphere"
+            center = [0, 0, 0]
+            radius = 1
+            material = { emittance = { type = "BlackBody", temperature = 5000 } }
+        "#;
+        let sphere: Surface = toml::from_str(sphere).unwrap();
+        let bvh = Arc::new(Bvh::new(
+            vec![sphere],
+            &BvhOptions {
+                max_leaf_size: 1,
+                builder: Builder::Median,
+            },
+        ));
+        let new_instance = |transform: &str| {
+            let mut instance: Surface = toml::from_str(&format!(
+                "type = 'Instance'\ngeometry = 'sphere'\ntransform = {transform}"
+            ))
+            .unwrap();
+            let Surface::Instance(inner) = &mut instance else {
+                unreachable!()
+            };
+            inner.resolve(bvh.clone());
+            instance
+        };
+        let mut surfaces = vec![
+            new_instance(
+                "[{ type = 'Scale', by = [2, 2, 2] }, { type = 'Translate', by = [0, 0, 10] }]",
+            ),
+            new_instance("[{ type = 'Translate', by = [0, 0, -10] }]"),
+        ];
+        let lights = Lights::new(&surfaces, None);
+        assert_eq!(lights.lights.len(), 1);
+
+        let mut rng = RandomSequence::new();
+        for selection in [0.25, 0.75] {
+            let sample = lights
+                .sample(Vec3::ZERO, selection, Vec2::new(0.3, 0.7))
+                .unwrap();
+            let ray = Ray::new(Vec3::ZERO, sample.direction);
+            let hit = surfaces
+                .iter()
+                .find_map(|surface| surface.hit(&ray, &(0.0..f64::INFINITY), &mut rng))
+                .unwrap();
+            assert!(lights.is_sampled(&sample, &hit));
+            assert_abs_diff_eq!(
+                lights.density(Vec3::ZERO, &hit),
+                sample.density,
+                epsilon = 1e-9 * sample.density,
+            );
+        }
+
+        // The stretched sphere cannot be sampled, and neither can the others of its material:
+        surfaces.push(new_instance("[{ type = 'Scale', by = [1, 2, 1] }]"));
+        assert!(Lights::new(&surfaces, None).lights.is_empty());
+    }
+}
@@ -1,21 +1,45 @@
-use std::f64::consts::FRAC_PI_2;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI, TAU};
 
+use crate::math::ray::Ray;
 use crate::math::vec2::Vec2;
 use crate::math::vec3::Vec3;
-use crate::scene::Camera;
+use crate::scene::{ApertureShape, Camera, Projection};
 
 pub struct Viewport {
     pub dx: Vec3,
     pub dy: Vec3,
 
-    image_half_size: Vec2,
+    image_size: Vec2,
+
+    projection: Projection,
+
+    location: Vec3,
+    look_at: Vec3,
+
+    /// Unit vector from the camera towards the «look at» point.
+    forward: Vec3,
+
+    /// Distance from the camera to the plane in focus.
+    focus_distance: f64,
+
+    /// Ratio of the focus distance to the distance to the «look at» point.
+    focus_scale: f64,
+
+    /// Lens basis vectors, scaled to the aperture radius.
+    lens_dx: Vec3,
+    lens_dy: Vec3,
+
+    /// Unit polygon vertices, or empty for the circular aperture.
+    aperture_vertices: Vec<Vec2>,
 }
 
 impl Viewport {
     /// Calculate and return the viewport's `dx` and `dy` vectors,
     /// which represent how much space the image pixel takes in the scene world.
     ///
-    /// The resulting vectors are relative to the camera direction point.
+    /// For the perspective and orthographic projections, the vectors are relative
+    /// to the camera direction point. For the angular projections, they are the unit
+    /// image axes.
     pub fn new(camera: &Camera, image_width: u32, image_height: u32) -> Self {
         let image_height = image_height as f64;
 
@@ -26,14 +50,45 @@ impl Viewport {
         let dx = principal_axis.cross(camera.up).normalize();
         let dy = dx.rotate_about(principal_axis, FRAC_PI_2);
 
-        // Finally, scale the vectors to the actual field-of-view angle:
-        let viewport_height = 2.0 * focal_length * (camera.vertical_fov / 2.0).to_radians().sin();
+        // Finally, scale the vectors to the actual viewport size:
+        let viewport_height = match camera.projection {
+            Projection::Perspective => {
+                2.0 * focal_length * (camera.vertical_fov / 2.0).to_radians().sin()
+            }
+            Projection::Orthographic { height } => height,
+            Projection::Fisheye { .. } | Projection::Equirectangular => image_height,
+        };
         let scale = viewport_height / image_height;
 
+        let aperture_radius = camera.aperture_radius();
+        let aperture_vertices = match camera.aperture.as_ref().map(|aperture| &aperture.shape) {
+            Some(ApertureShape::Polygon { n_blades, rotation }) => {
+                let n_blades = (*n_blades).max(3);
+                (0..n_blades)
+                    .map(|i| {
+                        let angle = rotation.to_radians() + TAU * i as f64 / n_blades as f64;
+                        let (sin, cos) = angle.sin_cos();
+                        Vec2::new(cos, sin)
+                    })
+                    .collect()
+            }
+            Some(ApertureShape::Circle) | None => Vec::new(),
+        };
+        let focus_distance = camera.focus_distance.unwrap_or(focal_length);
+
         Self {
             dx: dx * scale,
             dy: dy * scale,
-            image_half_size: Vec2::new(image_width as f64 / 2.0, image_height / 2.0),
+            image_size: Vec2::new(image_width as f64, image_height),
+            projection: camera.projection,
+            location: camera.location,
+            look_at: camera.look_at,
+            forward: -principal_axis,
+            focus_distance,
+            focus_scale: focus_distance / focal_length,
+            lens_dx: dx * aperture_radius,
+            lens_dy: dy * aperture_radius,
+            aperture_vertices,
         }
     }
 
@@ -43,13 +98,228 @@ impl Viewport {
         image_point.x * self.dx + image_point.y * self.dy
     }
 
-    /// Cast a ray to the specified image pixel coordinates and return the viewport vector.
+    /// Cast a ray through the specified image pixel coordinates.
     ///
-    /// # Notes
+    /// For the perspective and orthographic projections, the ray starts at the lens point,
+    /// which is picked by the `lens_sample` uniformly distributed within `[0, 1)`,
+    /// and goes through the pixel point on the focal plane.
     ///
-    /// You still **need** to add the resulting vector to the «look at» point.
+    /// Returns [`None`] when the pixel point is outside of the projection.
     #[inline]
-    pub fn cast_ray(&self, image_x: u32, image_y: u32, subpixel: Vec2) -> Vec3 {
-        self.at(Vec2::new(image_x, image_y) - self.image_half_size + subpixel)
+    pub fn cast_ray(
+        &self,
+        image_x: u32,
+        image_y: u32,
+        subpixel: Vec2,
+        lens_sample: Vec2,
+    ) -> Option<Ray> {
+        let image_point = Vec2::new(image_x, image_y) + subpixel;
+        let centered_point =
+            image_point - Vec2::new(self.image_size.x / 2.0, self.image_size.y / 2.0);
+
+        let (origin, focus_point) = match self.projection {
+            Projection::Perspective => {
+                let viewport_point = self.look_at + self.at(centered_point);
+                let focus_point =
+                    self.location + (viewport_point - self.location) * self.focus_scale;
+                (self.location, focus_point)
+            }
+            Projection::Orthographic { .. } => {
+                let origin = self.location + self.at(centered_point);
+                (origin, origin + self.forward * self.focus_distance)
+            }
+            Projection::Fisheye { fov } => {
+                let distance = (centered_point.x * centered_point.x
+                    + centered_point.y * centered_point.y)
+                    .sqrt();
+                let angle = distance * fov.to_radians() / self.image_size.y;
+                if angle > fov.to_radians() / 2.0 {
+                    return None;
+                }
+                let direction = if distance == 0.0 {
+                    self.forward
+                } else {
+                    let (sin, cos) = angle.sin_cos();
+                    self.forward * cos + self.at(centered_point) * (sin / distance)
+                };
+                return Some(Ray::new(self.location, direction));
+            }
+            Projection::Equirectangular => {
+                let longitude = (image_point.x / self.image_size.x - 0.5) * TAU;
+                let latitude = (0.5 - image_point.y / self.image_size.y) * PI;
+                let (sin_longitude, cos_longitude) = longitude.sin_cos();
+                let (sin_latitude, cos_latitude) = latitude.sin_cos();
+                let direction = (self.forward * cos_longitude + self.dx * sin_longitude)
+                    * cos_latitude
+                    - self.dy * sin_latitude;
+                return Some(Ray::new(self.location, direction));
+            }
+        };
+
+        let lens_point = self.sample_aperture(lens_sample);
+        let origin = origin + lens_point.x * self.lens_dx + lens_point.y * self.lens_dy;
+        Some(Ray::with_two_points(origin, focus_point))
+    }
+
+    /// Uniformly sample the unit aperture.
+    fn sample_aperture(&self, sample: Vec2) -> Vec2 {
+        if self.aperture_vertices.is_empty() {
+            Self::sample_disk(sample)
+        } else {
+            // Pick a polygon sector, and reuse the number for the point within it:
+            let n_sectors = self.aperture_vertices.len();
+            let sector = sample.x * n_sectors as f64;
+            let i = (sector as usize).min(n_sectors - 1);
+            let sqrt_u = (sector - i as f64).clamp(0.0, 1.0).sqrt();
+            let (a, b) = (self.aperture_vertices[i], self.aperture_vertices[(i + 1) % n_sectors]);
+            Vec2::new(
+                sqrt_u * ((1.0 - sample.y) * a.x + sample.y * b.x),
+                sqrt_u * ((1.0 - sample.y) * a.y + sample.y * b.y),
+            )
+        }
+    }
+
+    /// [Concentric mapping][1] of the unit square onto the unit disk.
+    ///
+    /// [1]: https://www.pbr-book.org/3ed-2018/Monte_Carlo_Integration/2D_Sampling_with_Multidimensional_Transformations#SamplingaUnitDisk
+    fn sample_disk(sample: Vec2) -> Vec2 {
+        let x = 2.0 * sample.x - 1.0;
+        let y = 2.0 * sample.y - 1.0;
+        if x == 0.0 && y == 0.0 {
+            return Vec2::new(0.0, 0.0);
+        }
+        let (radius, angle) = if x.abs() > y.abs() {
+            (x, FRAC_PI_4 * (y / x))
+        } else {
+            (y, FRAC_PI_2 - FRAC_PI_4 * (x / y))
+        };
+        let (sin, cos) = angle.sin_cos();
+        Vec2::new(radius * cos, radius * sin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::scene::{Aperture, ApertureSize};
+
+    /// All the rays through the same pixel must converge on the focal plane.
+    #[test]
+    fn focus_ok() {
+        let camera = Camera {
+            location: Vec3::new(0.0, 0.0, -10.0),
+            aperture: Some(Aperture {
+                size: ApertureSize::Radius { radius: 0.5 },
+                shape: ApertureShape::Circle,
+            }),
+            focus_distance: Some(5.0),
+            ..Camera::default()
+        };
+        let viewport = Viewport::new(&camera, 100, 100);
+        let subpixel = Vec2::new(0.5, 0.5);
+        let focus_points: Vec<Vec3> = [
+            Vec2::new(0.1, 0.2),
+            Vec2::new(0.9, 0.6),
+            Vec2::new(0.5, 0.5),
+        ]
+        .into_iter()
+        .map(|lens_sample| {
+            let ray = viewport.cast_ray(10, 20, subpixel, lens_sample).unwrap();
+            // The camera looks along the Z axis:
+            ray.at((-5.0 - ray.origin.z) / ray.direction.z)
+        })
+        .collect();
+        for point in &focus_points[1..] {
+            assert!(point.abs_diff_eq(focus_points[0], 1e-9), "{point} != {}", focus_points[0]);
+        }
+    }
+
+    #[test]
+    fn polygon_aperture_ok() {
+        let camera = Camera {
+            aperture: Some(Aperture {
+                size: ApertureSize::Radius { radius: 0.5 },
+                shape: ApertureShape::Polygon { n_blades: 6, rotation: 0.0 },
+            }),
+            ..Camera::default()
+        };
+        let viewport = Viewport::new(&camera, 100, 100);
+        let apothem = (TAU / 12.0).cos();
+        for i in 0..32 {
+            for j in 0..32 {
+                let sample = Vec2::new(i as f64 / 32.0, j as f64 / 32.0);
+                let point = viewport.sample_aperture(sample);
+                // Check the distance from the center along the normal of each side:
+                for k in 0..6 {
+                    let (sin, cos) = (TAU * (k as f64 + 0.5) / 6.0).sin_cos();
+                    assert!(point.x * cos + point.y * sin <= apothem + 1e-12);
+                }
+            }
+        }
+        assert_abs_diff_eq!(viewport.lens_dx.length(), 0.5, epsilon = 1e-12);
+    }
+
+    fn cast_center_and_edge(projection: Projection) -> (Ray, Option<Ray>) {
+        let camera = Camera {
+            location: Vec3::new(0.0, 0.0, -10.0),
+            projection,
+            ..Camera::default()
+        };
+        let viewport = Viewport::new(&camera, 200, 100);
+        let center = viewport.cast_ray(100, 50, Vec2::new(0.0, 0.0), Vec2::new(0.5, 0.5));
+        let edge = viewport.cast_ray(0, 50, Vec2::new(0.0, 0.0), Vec2::new(0.5, 0.5));
+        (center.unwrap(), edge)
+    }
+
+    #[test]
+    fn orthographic_ok() {
+        let (center, edge) = cast_center_and_edge(Projection::Orthographic { height: 4.0 });
+        let edge = edge.unwrap();
+        assert!(
+            center
+                .direction
+                .abs_diff_eq(Vec3::new(0.0, 0.0, 1.0), 1e-12)
+        );
+        assert!(edge.direction.abs_diff_eq(center.direction, 1e-12));
+        assert_abs_diff_eq!((edge.origin - center.origin).length(), 4.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn fisheye_ok() {
+        let (center, edge) = cast_center_and_edge(Projection::Fisheye { fov: 180.0 });
+        assert!(
+            center
+                .direction
+                .abs_diff_eq(Vec3::new(0.0, 0.0, 1.0), 1e-12)
+        );
+        assert!(edge.is_none(), "the image corner is outside of the field of view");
+
+        // The top edge is at the half of the field of view:
+        let camera = Camera {
+            projection: Projection::Fisheye { fov: 180.0 },
+            ..Camera::default()
+        };
+        let viewport = Viewport::new(&camera, 200, 100);
+        let top = viewport
+            .cast_ray(100, 0, Vec2::new(0.0, 0.0), Vec2::new(0.5, 0.5))
+            .unwrap();
+        assert!(top.direction.abs_diff_eq(Vec3::new(0.0, 1.0, 0.0), 1e-12), "{}", top.direction);
+    }
+
+    #[test]
+    fn equirectangular_ok() {
+        let (center, edge) = cast_center_and_edge(Projection::Equirectangular);
+        assert!(
+            center
+                .direction
+                .abs_diff_eq(Vec3::new(0.0, 0.0, 1.0), 1e-12)
+        );
+        assert!(
+            edge.unwrap()
+                .direction
+                .abs_diff_eq(Vec3::new(0.0, 0.0, -1.0), 1e-12)
+        );
     }
 }